@@ -0,0 +1,315 @@
+// src/achievements/mod.rs
+pub mod toast;
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::Score;
+use crate::engine::config::{AiDifficulty, ConfigError};
+use crate::profile::Stats;
+use crate::systems::GameEvent;
+
+pub use toast::Toasts;
+
+/// The built-in achievements, used when `Config::achievements_file` isn't
+/// set. Also an example of the file format.
+const DEFAULTS: &str = include_str!("defaults.json");
+
+/// Returns at this fraction of the maximum speed or above count as being at
+/// it, since the speed goes through a few float operations.
+const MAX_SPEED_TOLERANCE: f32 = 0.999;
+
+/// An achievement as defined in the achievements file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Achievement {
+    pub id: String, // Stored in the profile once unlocked, so keep it stable
+    pub name: String,
+    pub description: String,
+    pub condition: Condition,
+}
+
+/// What has to happen for an achievement to unlock, written as an object
+/// with a `type` field, e.g. `{ "type": "rally", "hits": 50 }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Condition {
+    /// The player wins a match, optionally against one difficulty and
+    /// conceding at most `max_conceded` points.
+    Win {
+        difficulty: Option<AiDifficulty>,
+        max_conceded: Option<u32>,
+    },
+    /// A rally reaches `hits` paddle hits.
+    Rally { hits: u32 },
+    /// The player returns the ball at `at_least` pixels per second, or at
+    /// `ball_max_speed` if not given.
+    ReturnSpeed { at_least: Option<f32> },
+    /// A lifetime stat of the profile reaches `at_least`.
+    Stat { stat: Stat, at_least: f64 },
+}
+
+/// Lifetime stats a `Condition::Stat` can check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stat {
+    MatchesPlayed,
+    MatchesWon,
+    PointsWon,
+    LongestRally,
+    FastestReturn,
+    TimePlayed,
+}
+
+impl Stat {
+    pub fn value(self, stats: &Stats) -> f64 {
+        match self {
+            Stat::MatchesPlayed => stats.matches_played as f64,
+            Stat::MatchesWon => stats.matches_won as f64,
+            Stat::PointsWon => stats.points_won as f64,
+            Stat::LongestRally => stats.longest_rally as f64,
+            Stat::FastestReturn => stats.fastest_return as f64,
+            Stat::TimePlayed => stats.time_played,
+        }
+    }
+}
+
+/// What conditions are checked against besides the frame's events.
+pub struct Context<'a> {
+    pub difficulty: AiDifficulty,
+    pub max_ball_speed: f32,
+    pub score: &'a Score,
+    pub stats: &'a Stats,
+}
+
+/// Checks achievement conditions against a stream of gameplay events, one
+/// frame at a time.
+pub struct Tracker {
+    achievements: Vec<Achievement>,
+}
+
+#[allow(dead_code)]
+impl Tracker {
+    pub fn new(achievements: Vec<Achievement>) -> Self {
+        Self { achievements }
+    }
+
+    pub fn achievements(&self) -> &[Achievement] {
+        &self.achievements
+    }
+
+    /// Every achievement whose condition one frame's `events` meet, in
+    /// definition order, whether or not it was unlocked before.
+    pub fn process<'a>(
+        &mut self,
+        events: impl IntoIterator<Item = &'a GameEvent>,
+        context: &Context,
+    ) -> Vec<&Achievement> {
+        let mut met = vec![false; self.achievements.len()];
+        for event in events {
+            match *event {
+                GameEvent::PaddleHit {
+                    speed,
+                    is_player,
+                    rally,
+                    ..
+                } => {
+                    for (index, achievement) in self.achievements.iter().enumerate() {
+                        met[index] |= match achievement.condition {
+                            Condition::Rally { hits } => rally >= hits,
+                            Condition::ReturnSpeed { at_least } => {
+                                let target = at_least
+                                    .unwrap_or(context.max_ball_speed * MAX_SPEED_TOLERANCE);
+                                is_player && speed >= target
+                            }
+                            _ => false,
+                        };
+                    }
+                }
+                GameEvent::MatchWon { player_won: true } => {
+                    for (index, achievement) in self.achievements.iter().enumerate() {
+                        if let Condition::Win {
+                            difficulty,
+                            max_conceded,
+                        } = achievement.condition
+                        {
+                            met[index] |= difficulty.is_none_or(|d| d == context.difficulty)
+                                && max_conceded.is_none_or(|max| context.score.ai_score <= max);
+                        }
+                    }
+                }
+                GameEvent::MatchWon { player_won: false }
+                | GameEvent::WallBounce { .. }
+                | GameEvent::Goal { .. } => {}
+            }
+        }
+
+        for (index, achievement) in self.achievements.iter().enumerate() {
+            if let Condition::Stat { stat, at_least } = achievement.condition {
+                met[index] |= stat.value(context.stats) >= at_least;
+            }
+        }
+
+        self.achievements
+            .iter()
+            .zip(met)
+            .filter_map(|(achievement, met)| met.then_some(achievement))
+            .collect()
+    }
+}
+
+/// The built-in achievements.
+pub fn defaults() -> Vec<Achievement> {
+    serde_json::from_str(DEFAULTS).expect("built-in achievements parse")
+}
+
+/// Reads achievement definitions from a JSON file holding a list of them.
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<Achievement>, ConfigError> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path).map_err(|e| ConfigError::Io(path.into(), e))?;
+    let achievements: Vec<Achievement> =
+        serde_json::from_str(&text).map_err(|e| ConfigError::Parse(path.into(), e))?;
+
+    let mut ids = HashSet::new();
+    if let Some(duplicate) = achievements
+        .iter()
+        .find(|achievement| !ids.insert(&achievement.id))
+    {
+        return Err(ConfigError::Invalid(format!(
+            "{}: achievement id '{}' is used twice",
+            path.display(),
+            duplicate.id
+        )));
+    }
+    Ok(achievements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::events::testing::{goal, hit};
+
+    fn ids(met: Vec<&Achievement>) -> Vec<&str> {
+        met.iter()
+            .map(|achievement| achievement.id.as_str())
+            .collect()
+    }
+
+    fn context<'a>(difficulty: AiDifficulty, score: &'a Score, stats: &'a Stats) -> Context<'a> {
+        Context {
+            difficulty,
+            max_ball_speed: 700.0,
+            score,
+            stats,
+        }
+    }
+
+    #[test]
+    fn rallies_and_fast_returns() {
+        let mut tracker = Tracker::new(defaults());
+        let (score, stats) = (Score::new(), Stats::default());
+        let context = context(AiDifficulty::Normal, &score, &stats);
+
+        // 49 hits, a goal, then 50 hits split across frames
+        let mut events: Vec<GameEvent> = (1..=49).map(|n| hit(300.0, true, n)).collect();
+        events.push(goal(false, 49));
+        assert!(tracker.process(&events, &context).is_empty());
+        let events: Vec<GameEvent> = (1..=49).map(|n| hit(300.0, false, n)).collect();
+        assert!(tracker.process(&events, &context).is_empty());
+        assert_eq!(
+            ids(tracker.process(&[hit(300.0, false, 50)], &context)),
+            ["marathon"]
+        );
+
+        // Only the player's returns count for speed
+        assert!(!ids(tracker.process(&[hit(700.0, false, 1)], &context)).contains(&"top_speed"));
+        assert!(ids(tracker.process(&[hit(699.9, true, 1)], &context)).contains(&"top_speed"));
+    }
+
+    #[test]
+    fn wins_by_score_and_difficulty() {
+        let mut tracker = Tracker::new(defaults());
+        let stats = Stats::default();
+        let won = [goal(true, 0), GameEvent::MatchWon { player_won: true }];
+
+        let shutout = Score {
+            player_score: 11,
+            ai_score: 0,
+        };
+        let met = tracker.process(&won, &context(AiDifficulty::Hard, &shutout, &stats));
+        assert_eq!(ids(met), ["first_win", "shutout", "giant_slayer"]);
+
+        let close = Score {
+            player_score: 10,
+            ai_score: 9,
+        };
+        let met = tracker.process(&won, &context(AiDifficulty::Easy, &close, &stats));
+        assert_eq!(ids(met), ["first_win"]);
+
+        let lost = [goal(false, 0), GameEvent::MatchWon { player_won: false }];
+        assert!(
+            tracker
+                .process(&lost, &context(AiDifficulty::Hard, &close, &stats))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn lifetime_stats() {
+        let mut tracker = Tracker::new(defaults());
+        let score = Score::new();
+        let mut stats = Stats {
+            matches_played: 24,
+            ..Stats::default()
+        };
+        assert!(
+            tracker
+                .process(&[], &context(AiDifficulty::Normal, &score, &stats))
+                .is_empty()
+        );
+        stats.matches_played = 25;
+        let met = tracker.process(&[], &context(AiDifficulty::Normal, &score, &stats));
+        assert_eq!(ids(met), ["veteran"]);
+    }
+
+    #[test]
+    fn loads_definitions() {
+        assert_eq!(defaults().len(), 6);
+
+        let dir = std::env::temp_dir().join(format!("pong_achievements_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("achievements.json");
+
+        std::fs::write(
+            &path,
+            r#"[{ "id": "ace", "name": "Ace", "description": "Win 10 points",
+                  "condition": { "type": "stat", "stat": "points_won", "at_least": 10 } }]"#,
+        )
+        .unwrap();
+        let achievements = load(&path).unwrap();
+        assert_eq!(
+            achievements[0].condition,
+            Condition::Stat {
+                stat: Stat::PointsWon,
+                at_least: 10.0
+            }
+        );
+
+        let twice = format!(
+            "[{0}, {0}]",
+            serde_json::to_string(&achievements[0]).unwrap()
+        );
+        std::fs::write(&path, twice).unwrap();
+        assert!(matches!(load(&path), Err(ConfigError::Invalid(_))));
+        std::fs::write(
+            &path,
+            r#"[{ "id": "x", "name": "X", "description": "",
+                                    "condition": { "type": "rally", "hitz": 5 } }]"#,
+        )
+        .unwrap();
+        assert!(matches!(load(&path), Err(ConfigError::Parse(..))));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
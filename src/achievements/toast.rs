@@ -0,0 +1,110 @@
+// src/achievements/toast.rs
+use std::collections::VecDeque;
+
+use crate::engine::font::{LINE_HEIGHT, text_width};
+use crate::engine::framebuffer::{Framebuffer, with_alpha};
+
+const LIFETIME: f32 = 4.0; // Seconds a toast stays up
+const FADE: f32 = 0.5; // Seconds it takes to fade out at the end
+const MAX_SHOWN: usize = 3; // Later toasts wait their turn
+const MARGIN: i32 = 8;
+
+const TITLE: u32 = 0xFFD040;
+const TEXT: u32 = 0xFFFFFF;
+const EDGE: u32 = 0xFFD040;
+
+/// A short notice in the corner of the screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    pub title: String,
+    pub text: String,
+    age: f32,
+}
+
+/// Toasts waiting and on screen, oldest first. Lives in the `World` as a
+/// resource; systems only push to it when it exists.
+#[derive(Debug, Default)]
+pub struct Toasts {
+    toasts: VecDeque<Toast>,
+}
+
+#[allow(dead_code)]
+impl Toasts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, title: impl Into<String>, text: impl Into<String>) {
+        self.toasts.push_back(Toast {
+            title: title.into(),
+            text: text.into(),
+            age: 0.0,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    /// The toasts on screen now.
+    pub fn shown(&self) -> impl Iterator<Item = &Toast> {
+        self.toasts.iter().take(MAX_SHOWN)
+    }
+
+    /// Ages the toasts on screen by `delta_time` real seconds, dropping
+    /// expired ones. Takes real time so toasts still leave while paused.
+    pub fn update(&mut self, delta_time: f32) {
+        for toast in self.toasts.iter_mut().take(MAX_SHOWN) {
+            toast.age += delta_time;
+        }
+        self.toasts.retain(|toast| toast.age < LIFETIME);
+    }
+
+    /// Stacks the toasts up from the bottom-right corner.
+    pub fn draw(&self, frame: &mut Framebuffer) {
+        let line_height = LINE_HEIGHT as i32;
+        let mut bottom = frame.height() as i32 - MARGIN;
+        for toast in self.shown() {
+            let alpha = ((LIFETIME - toast.age) / FADE).clamp(0.0, 1.0);
+            let text_width = text_width(&toast.title, 1).max(text_width(&toast.text, 1)) as i32;
+            let width = text_width + MARGIN * 2;
+            let height = line_height * 2 + MARGIN * 2;
+            let left = frame.width() as i32 - width - MARGIN;
+            let top = bottom - height;
+
+            frame.fill_rect(left, top, width, height, with_alpha(0x101018, 0.85 * alpha));
+            frame.rect(left, top, width, height, with_alpha(EDGE, alpha));
+            let x = left + MARGIN;
+            frame.text(&toast.title, x, top + MARGIN, 1, with_alpha(TITLE, alpha));
+            let text_y = top + MARGIN + line_height;
+            frame.text(&toast.text, x, text_y, 1, with_alpha(TEXT, alpha));
+            bottom = top - MARGIN;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toasts_queue_and_expire() {
+        let mut toasts = Toasts::new();
+        for index in 0..4 {
+            toasts.push("ACHIEVEMENT UNLOCKED", format!("NUMBER {}", index));
+        }
+        assert_eq!(toasts.shown().count(), MAX_SHOWN);
+
+        // The fourth waits until the first three have gone
+        toasts.update(LIFETIME - 0.1);
+        toasts.update(0.2);
+        let shown: Vec<_> = toasts.shown().map(|toast| toast.text.as_str()).collect();
+        assert_eq!(shown, ["NUMBER 3"]);
+
+        let mut frame = Framebuffer::new(320, 240);
+        toasts.draw(&mut frame);
+        assert!(frame.pixels().contains(&TITLE));
+        toasts.update(LIFETIME);
+        assert!(toasts.is_empty());
+    }
+}
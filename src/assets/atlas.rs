@@ -0,0 +1,87 @@
+// src/assets/atlas.rs
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::assets::{AssetError, Sprite, load_png};
+
+/// A region name and its `[x, y, width, height]` in pixels.
+pub type Region = (String, [usize; 4]);
+
+/// Named regions of one image, described by a text file:
+///
+/// ```text
+/// # Lines starting with '#' are comments
+/// image sprites.png
+/// paddle 0 0 20 100
+/// ball 20 0 48 12
+/// ```
+///
+/// The image path is relative to the atlas file. Each region is
+/// `name x y width height` in pixels.
+#[derive(Debug, Clone)]
+pub struct TextureAtlas {
+    regions: HashMap<String, Sprite>,
+}
+
+#[allow(dead_code)]
+impl TextureAtlas {
+    pub fn load(path: &Path) -> Result<Self, AssetError> {
+        let text = std::fs::read_to_string(path).map_err(|e| AssetError::Io(path.into(), e))?;
+        let (image_path, regions) =
+            Self::parse(&text).map_err(|(line, message)| AssetError::Atlas {
+                path: path.into(),
+                line,
+                message,
+            })?;
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        let image = load_png(&base.join(image_path))?;
+        Ok(Self::from_image(&image, &regions))
+    }
+
+    /// Cuts `regions` out of an already loaded image.
+    pub fn from_image(image: &Sprite, regions: &[Region]) -> Self {
+        let regions = regions
+            .iter()
+            .map(|(name, [x, y, w, h])| (name.clone(), image.sub_image(*x, *y, *w, *h)))
+            .collect();
+        Self { regions }
+    }
+
+    /// Parses an atlas description into the image path and its regions.
+    /// Errors carry the 1-based line number.
+    pub fn parse(text: &str) -> Result<(String, Vec<Region>), (usize, String)> {
+        let mut image = None;
+        let mut regions = Vec::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line_number = number + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["image", path] => image = Some(path.to_string()),
+                [name, x, y, w, h] => {
+                    let mut rect = [0; 4];
+                    for (value, text) in rect.iter_mut().zip([x, y, w, h]) {
+                        *value = text
+                            .parse()
+                            .map_err(|_| (line_number, format!("invalid number '{}'", text)))?;
+                    }
+                    regions.push((name.to_string(), rect));
+                }
+                _ => return Err((line_number, format!("unexpected line '{}'", line))),
+            }
+        }
+
+        let image = image.ok_or((0, "missing 'image' line".to_string()))?;
+        Ok((image, regions))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Sprite> {
+        self.regions.get(name)
+    }
+}
@@ -0,0 +1,221 @@
+// src/assets/mod.rs
+pub mod atlas;
+pub mod sprite;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+pub use atlas::TextureAtlas;
+pub use sprite::{Sprite, SpriteSheet};
+
+#[derive(Debug)]
+pub enum AssetError {
+    Io(PathBuf, std::io::Error),
+    Png(PathBuf, png::DecodingError),
+    Encode(PathBuf, png::EncodingError),
+    Gif(PathBuf, gif::EncodingError),
+    Atlas {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            AssetError::Png(path, e) => write!(f, "{}: invalid PNG: {}", path.display(), e),
+            AssetError::Encode(path, e) => {
+                write!(f, "{}: failed to write PNG: {}", path.display(), e)
+            }
+            AssetError::Gif(path, e) => write!(f, "{}: failed to write GIF: {}", path.display(), e),
+            AssetError::Atlas {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+/// Decodes a PNG of any colour type and bit depth into an RGBA sprite.
+pub fn load_png(path: &Path) -> Result<Sprite, AssetError> {
+    let file = File::open(path).map_err(|e| AssetError::Io(path.into(), e))?;
+    let mut decoder = png::Decoder::new(BufReader::new(file));
+    // Palettes and low bit depths become 8-bit samples, 16-bit is cut to 8
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder
+        .read_info()
+        .map_err(|e| AssetError::Png(path.into(), e))?;
+
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buffer)
+        .map_err(|e| AssetError::Png(path.into(), e))?;
+    let bytes = &buffer[..info.buffer_size()];
+
+    let rgba: Vec<u8> = match info.color_type {
+        png::ColorType::Rgba => bytes.to_vec(),
+        png::ColorType::Rgb => bytes
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 0xFF])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => bytes
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        // Indexed images were expanded to RGB(A) by the transformation
+        png::ColorType::Grayscale | png::ColorType::Indexed => {
+            bytes.iter().flat_map(|&g| [g, g, g, 0xFF]).collect()
+        }
+    };
+
+    Ok(Sprite::from_rgba(
+        info.width as usize,
+        info.height as usize,
+        &rgba,
+    ))
+}
+
+/// Writes 8-bit RGBA pixels to `path` as a PNG.
+pub fn save_png(path: &Path, width: usize, height: usize, rgba: &[u8]) -> Result<(), AssetError> {
+    let file = File::create(path).map_err(|e| AssetError::Io(path.into(), e))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let encode_error = |e| AssetError::Encode(path.into(), e);
+    let mut writer = encoder.write_header().map_err(encode_error)?;
+    writer.write_image_data(rgba).map_err(encode_error)?;
+    writer.finish().map_err(encode_error)
+}
+
+/// Resolves sprite names from `Config`. A name is looked up in the texture
+/// atlas first, and otherwise loaded as a PNG relative to the asset directory.
+pub struct Assets {
+    root: PathBuf,
+    atlas: Option<TextureAtlas>,
+    cache: HashMap<String, Sprite>,
+}
+
+#[allow(dead_code)]
+impl Assets {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            atlas: None,
+            cache: HashMap::new(),
+        }
+    }
+
+    pub fn load_atlas(&mut self, name: &str) -> Result<(), AssetError> {
+        self.atlas = Some(TextureAtlas::load(&self.root.join(name))?);
+        Ok(())
+    }
+
+    pub fn sprite(&mut self, name: &str) -> Result<Sprite, AssetError> {
+        if let Some(sprite) = self.atlas.as_ref().and_then(|atlas| atlas.get(name)) {
+            return Ok(sprite.clone());
+        }
+        if let Some(sprite) = self.cache.get(name) {
+            return Ok(sprite.clone());
+        }
+        let sprite = load_png(&self.root.join(name))?;
+        self.cache.insert(name.to_string(), sprite.clone());
+        Ok(sprite)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_png(path: &Path, width: u32, height: u32, color: png::ColorType, data: &[u8]) {
+        let file = File::create(path).unwrap();
+        let mut encoder = png::Encoder::new(file, width, height);
+        encoder.set_color(color);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(data)
+            .unwrap();
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pong_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn decodes_rgba_and_rgb_pngs() {
+        let dir = temp_dir("png");
+        let rgba = dir.join("rgba.png");
+        write_png(
+            &rgba,
+            2,
+            1,
+            png::ColorType::Rgba,
+            &[255, 0, 0, 128, 0, 255, 0, 255],
+        );
+        let sprite = load_png(&rgba).unwrap();
+        assert_eq!((sprite.width, sprite.height), (2, 1));
+        assert_eq!(sprite.pixels, vec![0x80FF_0000, 0xFF00_FF00]);
+        assert_eq!(sprite.to_rgba(), vec![255, 0, 0, 128, 0, 255, 0, 255]);
+
+        let rgb = dir.join("rgb.png");
+        write_png(&rgb, 1, 1, png::ColorType::Rgb, &[1, 2, 3]);
+        assert_eq!(load_png(&rgb).unwrap().pixels, vec![0xFF01_0203]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        let error = load_png(Path::new("does/not/exist.png")).unwrap_err();
+        assert!(matches!(error, AssetError::Io(..)));
+    }
+
+    #[test]
+    fn atlas_regions_resolve_before_files() {
+        let dir = temp_dir("atlas");
+        let pixels: Vec<u8> = (0..4u8).flat_map(|i| [i, i, i, 255]).collect();
+        write_png(&dir.join("sheet.png"), 4, 1, png::ColorType::Rgba, &pixels);
+        std::fs::write(
+            dir.join("game.atlas"),
+            "# test\nimage sheet.png\nright 2 0 2 1\n",
+        )
+        .unwrap();
+
+        let mut assets = Assets::new(&dir);
+        assets.load_atlas("game.atlas").unwrap();
+        assert_eq!(
+            assets.sprite("right").unwrap().pixels,
+            vec![0xFF02_0202, 0xFF03_0303]
+        );
+        assert_eq!(assets.sprite("sheet.png").unwrap().width, 4);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn atlas_parse_reports_line_numbers() {
+        let error = TextureAtlas::parse("image a.png\n\npaddle 0 0 x 4\n").unwrap_err();
+        assert_eq!(error.0, 3);
+    }
+
+    #[test]
+    fn sprite_sheet_splits_frames_and_wraps() {
+        let sheet = Sprite::new(4, 2, (0..8).collect());
+        let frames = SpriteSheet::new(&sheet, 2, 1);
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames.frame(1).unwrap().pixels, vec![2, 3]);
+        assert_eq!(frames.frame(6).unwrap().pixels, vec![4, 5]);
+    }
+}
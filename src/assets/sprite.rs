@@ -0,0 +1,110 @@
+// src/assets/sprite.rs
+use crate::engine::framebuffer::Framebuffer;
+
+/// An RGBA image, packed as 0xAARRGGBB to blit straight into a `Framebuffer`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sprite {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u32>,
+}
+
+#[allow(dead_code)]
+impl Sprite {
+    pub fn new(width: usize, height: usize, pixels: Vec<u32>) -> Self {
+        assert_eq!(pixels.len(), width * height, "sprite size mismatch");
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Builds a sprite from RGBA bytes, four per pixel.
+    pub fn from_rgba(width: usize, height: usize, rgba: &[u8]) -> Self {
+        let pixels = rgba
+            .chunks_exact(4)
+            .map(|p| (p[3] as u32) << 24 | (p[0] as u32) << 16 | (p[1] as u32) << 8 | p[2] as u32)
+            .collect();
+        Self::new(width, height, pixels)
+    }
+
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|&p| [(p >> 16) as u8, (p >> 8) as u8, p as u8, (p >> 24) as u8])
+            .collect()
+    }
+
+    /// Copies out a rectangle of the sprite, clipped to its bounds.
+    pub fn sub_image(&self, x: usize, y: usize, width: usize, height: usize) -> Self {
+        let width = width.min(self.width.saturating_sub(x));
+        let height = height.min(self.height.saturating_sub(y));
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in y..y + height {
+            let start = row * self.width + x;
+            pixels.extend_from_slice(&self.pixels[start..start + width]);
+        }
+        Self::new(width, height, pixels)
+    }
+
+    pub fn draw(&self, framebuffer: &mut Framebuffer, x: i32, y: i32) {
+        framebuffer.blit(x, y, self.width, self.height, &self.pixels);
+    }
+
+    /// Draws the sprite stretched to `width` x `height` pixels.
+    pub fn draw_scaled(
+        &self,
+        framebuffer: &mut Framebuffer,
+        x: i32,
+        y: i32,
+        width: usize,
+        height: usize,
+    ) {
+        framebuffer.blit_scaled(x, y, width, height, self.width, self.height, &self.pixels);
+    }
+}
+
+/// A strip or grid of equally sized animation frames, read left to right,
+/// top to bottom.
+#[derive(Debug, Clone)]
+pub struct SpriteSheet {
+    frames: Vec<Sprite>,
+}
+
+#[allow(dead_code)]
+impl SpriteSheet {
+    pub fn new(sheet: &Sprite, frame_width: usize, frame_height: usize) -> Self {
+        let mut frames = Vec::new();
+        if frame_width > 0 && frame_height > 0 {
+            for row in 0..sheet.height / frame_height {
+                for col in 0..sheet.width / frame_width {
+                    frames.push(sheet.sub_image(
+                        col * frame_width,
+                        row * frame_height,
+                        frame_width,
+                        frame_height,
+                    ));
+                }
+            }
+        }
+        Self { frames }
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The frame at `index`, wrapping around so animations can just count up.
+    pub fn frame(&self, index: usize) -> Option<&Sprite> {
+        if self.frames.is_empty() {
+            None
+        } else {
+            self.frames.get(index % self.frames.len())
+        }
+    }
+}
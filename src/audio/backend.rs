@@ -0,0 +1,117 @@
+// src/audio/backend.rs
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::audio::Sound;
+use crate::error;
+
+/// Somewhere synthesized sounds can be sent.
+pub trait AudioBackend {
+    fn sample_rate(&self) -> u32;
+    fn play(&mut self, sound: Sound, samples: &[f32]);
+}
+
+/// Shared list of every sound a backend was asked to play, in order.
+pub type PlayLog = Rc<RefCell<Vec<Sound>>>;
+
+/// Plays nothing, only records which sounds were requested.
+pub struct NullBackend {
+    log: PlayLog,
+}
+
+#[allow(dead_code)]
+impl NullBackend {
+    pub fn new() -> Self {
+        Self {
+            log: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    pub fn log(&self) -> PlayLog {
+        Rc::clone(&self.log)
+    }
+}
+
+impl AudioBackend for NullBackend {
+    fn sample_rate(&self) -> u32 {
+        44_100
+    }
+
+    fn play(&mut self, sound: Sound, _samples: &[f32]) {
+        self.log.borrow_mut().push(sound);
+    }
+}
+
+/// Writes every played sound to its own numbered WAV file in a directory.
+pub struct WavBackend {
+    directory: PathBuf,
+    sample_rate: u32,
+    count: usize,
+    log: PlayLog,
+}
+
+#[allow(dead_code)]
+impl WavBackend {
+    pub fn new(directory: impl Into<PathBuf>, sample_rate: u32) -> io::Result<Self> {
+        let directory = directory.into();
+        std::fs::create_dir_all(&directory)?;
+        Ok(Self {
+            directory,
+            sample_rate,
+            count: 0,
+            log: Rc::new(RefCell::new(Vec::new())),
+        })
+    }
+
+    pub fn log(&self) -> PlayLog {
+        Rc::clone(&self.log)
+    }
+}
+
+impl AudioBackend for WavBackend {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn play(&mut self, sound: Sound, samples: &[f32]) {
+        let path = self
+            .directory
+            .join(format!("{:04}_{}.wav", self.count, sound.name()));
+        self.count += 1;
+        self.log.borrow_mut().push(sound);
+
+        if let Err(e) = write_wav(&path, samples, self.sample_rate) {
+            error!("failed to write sound"; path = path.display(), error = e);
+        }
+    }
+}
+
+/// Writes mono samples as a 16-bit PCM WAV file.
+pub fn write_wav(path: &Path, samples: &[f32], sample_rate: u32) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let data_len = (samples.len() * 2) as u32;
+
+    out.write_all(b"RIFF")?;
+    out.write_all(&(36 + data_len).to_le_bytes())?;
+    out.write_all(b"WAVE")?;
+
+    out.write_all(b"fmt ")?;
+    out.write_all(&16u32.to_le_bytes())?; // Chunk size
+    out.write_all(&1u16.to_le_bytes())?; // PCM
+    out.write_all(&1u16.to_le_bytes())?; // Mono
+    out.write_all(&sample_rate.to_le_bytes())?;
+    out.write_all(&(sample_rate * 2).to_le_bytes())?; // Byte rate
+    out.write_all(&2u16.to_le_bytes())?; // Block align
+    out.write_all(&16u16.to_le_bytes())?; // Bits per sample
+
+    out.write_all(b"data")?;
+    out.write_all(&data_len.to_le_bytes())?;
+    for &sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        out.write_all(&value.to_le_bytes())?;
+    }
+    out.flush()
+}
@@ -0,0 +1,80 @@
+// src/audio/device.rs
+use std::sync::{Arc, Mutex};
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+use crate::audio::{AudioBackend, Sound};
+use crate::engine::error::EngineError;
+use crate::error;
+
+// A sound being played and how far into it the device has got.
+struct Voice {
+    samples: Vec<f32>,
+    position: usize,
+}
+
+/// Plays sounds on the default output device, mixing overlapping ones.
+pub struct DeviceBackend {
+    voices: Arc<Mutex<Vec<Voice>>>,
+    sample_rate: u32,
+    _stream: cpal::Stream,
+}
+
+impl DeviceBackend {
+    pub fn new() -> Result<Self, EngineError> {
+        let host = cpal::default_host();
+        let device = host
+            .default_output_device()
+            .ok_or_else(|| EngineError::Audio("no audio output device".to_string()))?;
+        let config = device
+            .default_output_config()
+            .map_err(|e| EngineError::Audio(e.to_string()))?;
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels() as usize;
+
+        let voices: Arc<Mutex<Vec<Voice>>> = Arc::new(Mutex::new(Vec::new()));
+        let mixer_voices = Arc::clone(&voices);
+
+        let stream = device
+            .build_output_stream(
+                &config.into(),
+                move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                    let mut voices = mixer_voices.lock().unwrap_or_else(|e| e.into_inner());
+                    for frame in data.chunks_mut(channels) {
+                        let mut mixed = 0.0;
+                        for voice in voices.iter_mut() {
+                            if let Some(sample) = voice.samples.get(voice.position) {
+                                mixed += sample;
+                                voice.position += 1;
+                            }
+                        }
+                        frame.fill(mixed.clamp(-1.0, 1.0));
+                    }
+                    voices.retain(|voice| voice.position < voice.samples.len());
+                },
+                |e| error!("audio stream error"; error = e),
+                None,
+            )
+            .map_err(|e| EngineError::Audio(e.to_string()))?;
+        stream.play().map_err(|e| EngineError::Audio(e.to_string()))?;
+
+        Ok(Self {
+            voices,
+            sample_rate,
+            _stream: stream,
+        })
+    }
+}
+
+impl AudioBackend for DeviceBackend {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn play(&mut self, _sound: Sound, samples: &[f32]) {
+        self.voices.lock().unwrap_or_else(|e| e.into_inner()).push(Voice {
+            samples: samples.to_vec(),
+            position: 0,
+        });
+    }
+}
@@ -0,0 +1,161 @@
+// src/audio/mod.rs
+pub mod backend;
+#[cfg(feature = "audio-device")]
+pub mod device;
+pub mod synth;
+
+use std::collections::HashMap;
+
+pub use backend::{AudioBackend, NullBackend};
+use synth::{Tone, Waveform};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sound {
+    PaddleHit,
+    WallBounce,
+    Score,
+    Win,
+}
+
+impl Sound {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Sound::PaddleHit => "paddle_hit",
+            Sound::WallBounce => "wall_bounce",
+            Sound::Score => "score",
+            Sound::Win => "win",
+        }
+    }
+
+    /// The classic beeps: a short high square wave for the paddle, a lower
+    /// one for walls, a falling sine for a point and a rising arpeggio for
+    /// the win.
+    fn tones(&self) -> Vec<Tone> {
+        match self {
+            Sound::PaddleHit => vec![Tone::new(Waveform::Square, 880.0, 0.05)],
+            Sound::WallBounce => vec![Tone::new(Waveform::Square, 440.0, 0.04)],
+            Sound::Score => vec![
+                Tone::new(Waveform::Sine, 523.25, 0.12),
+                Tone::new(Waveform::Sine, 261.63, 0.25),
+            ],
+            Sound::Win => vec![
+                Tone::new(Waveform::Square, 523.25, 0.1),
+                Tone::new(Waveform::Square, 659.25, 0.1),
+                Tone::new(Waveform::Square, 783.99, 0.1),
+                Tone::new(Waveform::Square, 1046.5, 0.3),
+            ],
+        }
+    }
+}
+
+/// Synthesizes sounds on demand, caches them and hands them to a backend.
+pub struct Audio {
+    backend: Box<dyn AudioBackend>,
+    cache: HashMap<Sound, Vec<f32>>,
+    pub volume: f32,
+    pub muted: bool,
+}
+
+impl Audio {
+    pub fn new(backend: Box<dyn AudioBackend>) -> Self {
+        Self {
+            backend,
+            cache: HashMap::new(),
+            volume: 1.0,
+            muted: false,
+        }
+    }
+
+    pub fn play(&mut self, sound: Sound) {
+        if self.muted || self.volume <= 0.0 {
+            return;
+        }
+
+        let sample_rate = self.backend.sample_rate();
+        let samples = self
+            .cache
+            .entry(sound)
+            .or_insert_with(|| synth::render(&sound.tones(), sample_rate));
+
+        let volume = self.volume.min(1.0);
+        let scaled: Vec<f32> = samples.iter().map(|s| s * volume).collect();
+        self.backend.play(sound, &scaled);
+    }
+}
+
+/// The system audio device when built with the `audio-device` feature and
+/// one is available, the silent backend otherwise.
+pub fn default_backend() -> Box<dyn AudioBackend> {
+    #[cfg(feature = "audio-device")]
+    match device::DeviceBackend::new() {
+        Ok(backend) => return Box::new(backend),
+        Err(e) => crate::warn!("audio disabled"; error = e),
+    }
+    Box::new(NullBackend::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::backend::{WavBackend, write_wav};
+    use super::synth::render;
+    use super::*;
+
+    #[test]
+    fn rendered_tones_have_expected_length_and_range() {
+        let samples = render(&Sound::Score.tones(), 8_000);
+        assert_eq!(samples.len(), (0.12 * 8_000.0) as usize + (0.25 * 8_000.0) as usize);
+        assert!(samples.iter().all(|s| (-1.0..=1.0).contains(s)));
+    }
+
+    #[test]
+    fn envelope_fades_in_and_out() {
+        let tone = Tone::new(Waveform::Square, 440.0, 0.1);
+        assert_eq!(tone.envelope.level(0.0, tone.duration), 0.0);
+        assert!(tone.envelope.level(tone.duration, tone.duration) < 1e-3);
+        assert!(tone.envelope.level(0.03, tone.duration) > 0.5);
+    }
+
+    #[test]
+    fn muted_audio_plays_nothing() {
+        let backend = NullBackend::new();
+        let log = backend.log();
+        let mut audio = Audio::new(Box::new(backend));
+
+        audio.muted = true;
+        audio.play(Sound::PaddleHit);
+        audio.muted = false;
+        audio.play(Sound::WallBounce);
+
+        assert_eq!(*log.borrow(), vec![Sound::WallBounce]);
+    }
+
+    #[test]
+    fn wav_backend_writes_a_file_per_sound() {
+        let directory = std::env::temp_dir().join(format!("pong_wav_{}", std::process::id()));
+        let backend = WavBackend::new(&directory, 8_000).unwrap();
+        let log = backend.log();
+        let mut audio = Audio::new(Box::new(backend));
+
+        audio.play(Sound::PaddleHit);
+        audio.play(Sound::Win);
+
+        assert_eq!(*log.borrow(), vec![Sound::PaddleHit, Sound::Win]);
+        let bytes = std::fs::read(directory.join("0000_paddle_hit.wav")).unwrap();
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(&bytes[8..12], b"WAVE");
+        assert_eq!(bytes.len(), 44 + (0.05 * 8_000.0) as usize * 2);
+        assert!(directory.join("0001_win.wav").exists());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn wav_header_records_sample_rate() {
+        let path = std::env::temp_dir().join(format!("pong_header_{}.wav", std::process::id()));
+        write_wav(&path, &[0.0, 1.0, -1.0], 22_050).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(u32::from_le_bytes(bytes[24..28].try_into().unwrap()), 22_050);
+        assert_eq!(i16::from_le_bytes(bytes[46..48].try_into().unwrap()), i16::MAX);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,86 @@
+// src/audio/synth.rs
+use std::f32::consts::TAU;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Waveform {
+    Square,
+    Sine,
+}
+
+/// Linear attack/decay/release envelope. Times are in seconds, `sustain` is
+/// the level held between the decay and the release.
+#[derive(Debug, Clone, Copy)]
+pub struct Envelope {
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub release: f32,
+}
+
+impl Envelope {
+    /// Amplitude at `t` seconds into a note lasting `duration` seconds.
+    pub fn level(&self, t: f32, duration: f32) -> f32 {
+        let release_start = (duration - self.release).max(0.0);
+        let level = if t < self.attack {
+            t / self.attack
+        } else if t < self.attack + self.decay {
+            1.0 - (1.0 - self.sustain) * (t - self.attack) / self.decay
+        } else {
+            self.sustain
+        };
+
+        if t >= release_start && self.release > 0.0 {
+            level * (1.0 - (t - release_start) / self.release).max(0.0)
+        } else {
+            level
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Tone {
+    pub waveform: Waveform,
+    pub frequency: f32,
+    pub duration: f32,
+    pub envelope: Envelope,
+}
+
+impl Tone {
+    pub fn new(waveform: Waveform, frequency: f32, duration: f32) -> Self {
+        Self {
+            waveform,
+            frequency,
+            duration,
+            envelope: Envelope {
+                attack: 0.002,
+                decay: duration * 0.3,
+                sustain: 0.6,
+                release: duration * 0.4,
+            },
+        }
+    }
+}
+
+/// Renders tones one after the other into mono samples in `[-1, 1]`.
+pub fn render(tones: &[Tone], sample_rate: u32) -> Vec<f32> {
+    let mut samples = Vec::new();
+    for tone in tones {
+        let count = (tone.duration * sample_rate as f32) as usize;
+        for i in 0..count {
+            let t = i as f32 / sample_rate as f32;
+            let phase = (t * tone.frequency).fract();
+            let wave = match tone.waveform {
+                Waveform::Square => {
+                    if phase < 0.5 {
+                        1.0
+                    } else {
+                        -1.0
+                    }
+                }
+                Waveform::Sine => (phase * TAU).sin(),
+            };
+            samples.push(wave * tone.envelope.level(t, tone.duration));
+        }
+    }
+    samples
+}
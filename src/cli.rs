@@ -0,0 +1,138 @@
+// src/cli.rs
+use std::path::PathBuf;
+
+use crate::engine::Config;
+use crate::log::Level;
+
+pub const USAGE: &str = "\
+Usage: ping_pong_engine [OPTIONS]
+
+Options:
+  --config <FILE>        Load settings from a JSON file and reload it when it changes (default config.json)
+  --record-match <FILE>  Play an AI-vs-AI match without a window and record it to a GIF
+  --max-frames <N>       Stop a recorded match after N frames (default 3600)
+  --log-level <LEVEL>    Log error, warn, info, debug or trace messages (overrides the config)
+  --log-file <FILE>      Also log to FILE, rotated by size (overrides the config)
+  --profile <NAME>       Record stats to the player profile NAME (overrides the config)
+  -h, --help             Print this help";
+
+#[derive(Debug, PartialEq)]
+pub struct Options {
+    pub config: PathBuf,
+    pub record_match: Option<PathBuf>,
+    pub max_frames: u64,
+    pub log_level: Option<Level>,
+    pub log_file: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            config: PathBuf::from("config.json"),
+            record_match: None,
+            max_frames: 3600, // A minute at 60 FPS
+            log_level: None,
+            log_file: None,
+            profile: None,
+            help: false,
+        }
+    }
+}
+
+/// Parses the arguments after the program name.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} needs a value", name));
+        match arg.as_str() {
+            "--config" => options.config = value(&arg)?.into(),
+            "--record-match" => options.record_match = Some(value(&arg)?.into()),
+            "--max-frames" => {
+                let frames = value(&arg)?;
+                options.max_frames = frames
+                    .parse()
+                    .map_err(|_| format!("--max-frames: '{}' is not a frame count", frames))?;
+            }
+            "--log-level" => options.log_level = Some(value(&arg)?.parse()?),
+            "--log-file" => options.log_file = Some(value(&arg)?.into()),
+            "--profile" => options.profile = Some(value(&arg)?),
+            "-h" | "--help" => options.help = true,
+            _ => return Err(format!("unknown option '{}'", arg)),
+        }
+    }
+    Ok(options)
+}
+
+impl Options {
+    /// Overrides the settings given on the command line, so they survive
+    /// config reloads.
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(level) = self.log_level {
+            config.log_level = level;
+        }
+        if let Some(file) = &self.log_file {
+            config.log_file = Some(file.to_string_lossy().into_owned());
+        }
+        if let Some(profile) = &self.profile {
+            config.profile_name = profile.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(args: &[&str]) -> Result<Options, String> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_record_options() {
+        let options = parse_str(&[
+            "--record-match",
+            "match.gif",
+            "--max-frames",
+            "120",
+            "--config",
+            "tuning.json",
+        ])
+        .unwrap();
+        assert_eq!(options.record_match, Some(PathBuf::from("match.gif")));
+        assert_eq!(options.config, PathBuf::from("tuning.json"));
+        assert_eq!(options.max_frames, 120);
+        assert_eq!(parse_str(&[]).unwrap(), Options::default());
+    }
+
+    #[test]
+    fn options_override_the_config() {
+        let options = parse_str(&[
+            "--log-level",
+            "debug",
+            "--log-file",
+            "pong.log",
+            "--profile",
+            "Ann",
+        ])
+        .unwrap();
+        let mut config = Config::default();
+        options.apply(&mut config);
+        assert_eq!(config.log_level, Level::Debug);
+        assert_eq!(config.log_file.as_deref(), Some("pong.log"));
+        assert_eq!(config.profile_name, "Ann");
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(parse_str(&["--record-match"]).is_err());
+        assert!(parse_str(&["--config"]).is_err());
+        assert!(parse_str(&["--profile"]).is_err());
+        assert!(parse_str(&["--log-level", "loud"]).is_err());
+        assert!(parse_str(&["--max-frames", "lots"]).is_err());
+        assert!(parse_str(&["--fast"]).is_err());
+    }
+}
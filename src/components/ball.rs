@@ -7,23 +7,84 @@ pub struct Ball {
     // pub color: String,
     pub velocity_x: f32,
     pub velocity_y: f32,
+    pub spin: f32,     // Angular velocity in rad/s, positive is clockwise on screen
+    pub rotation: f32, // Accumulated spin angle, used to draw the spin marker
 }
 
 impl Ball {
-    pub fn new(x: f32, y: f32) -> Self {
+    /// Slope of the serve, the ball leaves the centre going 2 across for 1 down.
+    const SERVE_DIRECTION: (f32, f32) = (0.894_427_2, 0.447_213_6);
+
+    pub fn new(x: f32, y: f32, speed: f32) -> Self {
         Self {
-            x: x,
-            y: y,
-            velocity_x: 200.0, // Initial velocity
-            velocity_y: 100.0,
+            x,
+            y,
+            velocity_x: speed * Self::SERVE_DIRECTION.0, // Initial velocity
+            velocity_y: speed * Self::SERVE_DIRECTION.1,
+            spin: 0.0,
+            rotation: 0.0,
         }
     }
 
-    pub fn reset(&mut self, x: f32, y: f32) {
+    /// Serves the ball from `(x, y)` towards the player who just conceded,
+    /// back at serve speed.
+    pub fn reset(&mut self, x: f32, y: f32, speed: f32) {
+        // It keeps heading the way it left the court
+        let direction = if self.velocity_x > 0.0 { 1.0 } else { -1.0 };
         self.x = x;
         self.y = y;
-        self.velocity_x = if self.velocity_x > 0.0 { -200.0 } else { 200.0 };
-        self.velocity_y = 100.0;
+        self.velocity_x = direction * speed * Self::SERVE_DIRECTION.0;
+        self.velocity_y = speed * Self::SERVE_DIRECTION.1;
+        self.spin = 0.0;
+        self.rotation = 0.0;
+    }
+
+    pub fn speed(&self) -> f32 {
+        (self.velocity_x * self.velocity_x + self.velocity_y * self.velocity_y).sqrt()
+    }
+
+    /// Rescales the velocity to `speed` without changing its direction.
+    pub fn set_speed(&mut self, speed: f32) {
+        let current = self.speed();
+        if current > 0.0 {
+            self.velocity_x *= speed / current;
+            self.velocity_y *= speed / current;
+        }
+    }
+
+    /// Caps the speed and keeps the direction within `max_angle` radians of
+    /// horizontal, so the ball can never end up travelling almost straight
+    /// up and down.
+    pub fn limit_velocity(&mut self, max_speed: f32, max_angle: f32) {
+        let speed = self.speed().min(max_speed);
+        let angle = self.velocity_y.abs().atan2(self.velocity_x.abs());
+        let angle = angle.min(max_angle);
+        self.velocity_x = self.velocity_x.signum() * speed * angle.cos();
+        self.velocity_y = self.velocity_y.signum() * speed * angle.sin();
     }
 }
 
+/// Marks a ball spawned by multiball. Extra balls are removed when they
+/// leave the field instead of being served again.
+#[derive(Debug, Clone, Copy)]
+pub struct ExtraBall;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_towards_whoever_conceded() {
+        // Out past the AI on the right: the AI receives the serve
+        let mut ball = Ball::new(810.0, 300.0, 500.0);
+        ball.reset(400.0, 300.0, 300.0);
+        assert!(ball.velocity_x > 0.0);
+        assert_eq!((ball.x, ball.y), (400.0, 300.0));
+        assert!((ball.speed() - 300.0).abs() < 0.01);
+
+        // Out past the player on the left: the player receives it
+        ball.velocity_x = -ball.velocity_x;
+        ball.reset(400.0, 300.0, 300.0);
+        assert!(ball.velocity_x < 0.0);
+    }
+}
@@ -0,0 +1,47 @@
+// src/components/debug.rs
+use std::collections::VecDeque;
+
+/// A collision the physics resolved: where it happened and the normal the
+/// ball bounced off.
+#[derive(Debug, Clone, Copy)]
+pub struct Contact {
+    pub x: f32,
+    pub y: f32,
+    pub normal_x: f32,
+    pub normal_y: f32,
+    pub age: f32, // Seconds since the contact
+}
+
+/// Where an AI paddle expects to meet the ball, and the ball's path there.
+#[derive(Debug, Clone)]
+pub struct Intercept {
+    pub x: f32,
+    pub y: f32,
+    pub path: Vec<(f32, f32)>, // From the ball through each wall bounce
+}
+
+/// Simulation state the systems publish for the debug overlay. Systems only
+/// write to it when the resource exists.
+#[derive(Debug, Default)]
+pub struct DebugInfo {
+    pub tick: u64,
+    pub contacts: VecDeque<Contact>,
+    pub intercepts: Vec<Intercept>,
+}
+
+#[allow(dead_code)]
+impl DebugInfo {
+    pub const CONTACT_LIFETIME: f32 = 1.5;
+    const MAX_CONTACTS: usize = 16;
+
+    pub fn add_contact(&mut self, x: f32, y: f32, normal_x: f32, normal_y: f32) {
+        self.contacts.push_front(Contact {
+            x,
+            y,
+            normal_x,
+            normal_y,
+            age: 0.0,
+        });
+        self.contacts.truncate(Self::MAX_CONTACTS);
+    }
+}
@@ -0,0 +1,56 @@
+// src/components/effects.rs
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone)]
+pub struct Particle {
+    pub x: f32,
+    pub y: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+    pub age: f32,
+    pub lifetime: f32,
+    pub size: f32,
+    pub start_color: u32, // 0xRRGGBB
+    pub end_color: u32,
+}
+
+impl Particle {
+    /// How far through its life the particle is, from 0.0 to 1.0.
+    pub fn progress(&self) -> f32 {
+        (self.age / self.lifetime).min(1.0)
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.age < self.lifetime
+    }
+}
+
+/// Every live particle, kept in one resource rather than as entities since
+/// there can be hundreds of them.
+#[derive(Debug, Clone, Default)]
+pub struct Particles {
+    pub particles: Vec<Particle>,
+}
+
+/// Recent positions of a ball, newest first.
+#[derive(Debug, Clone, Default)]
+pub struct Trail {
+    pub points: VecDeque<(f32, f32)>,
+}
+
+/// Camera shake after a goal. `offset` is what the renderer applies this frame.
+#[derive(Debug, Clone, Default)]
+pub struct ScreenShake {
+    pub time_left: f32,
+    pub duration: f32,
+    pub intensity: f32,
+    pub offset: (i32, i32),
+}
+
+impl ScreenShake {
+    pub fn start(&mut self, intensity: f32, duration: f32) {
+        self.intensity = intensity;
+        self.duration = duration;
+        self.time_left = duration;
+    }
+}
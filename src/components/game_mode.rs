@@ -0,0 +1,22 @@
+// src/components/game_mode.rs
+use crate::ecs::World;
+
+/// Who is playing the match in progress. Only single player matches count
+/// towards the profile, the achievements and the leaderboard.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GameMode {
+    #[default]
+    Single, // The player on the left against the AI
+    Versus, // Two players sharing the keyboard
+}
+
+impl GameMode {
+    /// The world's mode, single player if it has none.
+    pub fn of(world: &World) -> Self {
+        if world.has_resource::<GameMode>() {
+            *world.resource::<GameMode>()
+        } else {
+            GameMode::Single
+        }
+    }
+}
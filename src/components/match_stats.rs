@@ -0,0 +1,18 @@
+// src/components/match_stats.rs
+/// Running stats for the match in progress, for the game over screen and
+/// the leaderboard. Reset when a new match starts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MatchStats {
+    pub longest_rally: u32, // Paddle hits between two points
+    pub duration: f32,      // Seconds of unpaused play
+}
+
+impl MatchStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
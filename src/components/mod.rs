@@ -1,8 +1,18 @@
 // src/components/mod.rs
 pub mod ball;
+pub mod debug;
+pub mod effects;
+pub mod game_mode;
+pub mod match_stats;
 pub mod paddle;
+pub mod rally;
 pub mod score;
 
-pub use ball::Ball;
+pub use ball::{Ball, ExtraBall};
+pub use debug::{DebugInfo, Intercept};
+pub use effects::{Particle, Particles, ScreenShake, Trail};
+pub use game_mode::GameMode;
+pub use match_stats::MatchStats;
 pub use paddle::Paddle;
+pub use rally::Rally;
 pub use score::Score;
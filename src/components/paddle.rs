@@ -5,6 +5,7 @@ pub struct Paddle {
     pub y: f32,
     pub velocity_y: f32,
     pub is_player: bool,
+    pub is_second_player: bool, // Played from the arrow keys in a versus match
 }
 
 impl Paddle {
@@ -14,6 +15,7 @@ impl Paddle {
             y,
             velocity_y: 0.0,
             is_player,
+            is_second_player: false,
         }
     }
 }
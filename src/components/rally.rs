@@ -0,0 +1,20 @@
+// src/components/rally.rs
+/// Paddle hits since the last point was scored.
+#[derive(Debug, Clone, Default)]
+pub struct Rally {
+    pub hits: u32,
+}
+
+impl Rally {
+    pub fn new() -> Self {
+        Self { hits: 0 }
+    }
+
+    pub fn hit(&mut self) {
+        self.hits += 1;
+    }
+
+    pub fn reset(&mut self) {
+        self.hits = 0;
+    }
+}
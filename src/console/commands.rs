@@ -0,0 +1,385 @@
+// src/console/commands.rs
+use std::path::Path;
+
+use crate::components::{Ball, ExtraBall, Score};
+use crate::ecs::World;
+use crate::engine::config::AiDifficulty;
+use crate::engine::profiler::Profiler;
+use crate::engine::{Config, Engine};
+use crate::leaderboard::Leaderboard;
+use crate::profile::{self, Profile};
+
+const SLOW_MOTION: f32 = 0.25; // Time scale `slowmo` uses when none is given
+const PROFILE_CSV: &str = "profile.csv";
+const LEADERBOARD_EXPORT: &str = "leaderboard_export.json";
+const MAX_STEP: u32 = 3600; // Most frames one `step` queues, a minute at 60 Hz
+
+/// Console commands and their usage, shown by `help`.
+pub const COMMANDS: [(&str, &str); 14] = [
+    ("help", "help"),
+    ("set", "set <field> <value>"),
+    ("get", "get <field>"),
+    ("spawn", "spawn ball [x y]"),
+    ("score", "score <player> <ai>"),
+    ("ai", "ai <easy|normal|hard>"),
+    ("slowmo", "slowmo [scale|off]"),
+    ("pause", "pause [on|off]"),
+    ("step", "step [frames]"),
+    ("record", "record [start|stop]"),
+    ("profile", "profile [dump [file]|reset]"),
+    ("stats", "stats"),
+    ("leaderboard", "leaderboard [export [file]]"),
+    ("clear", "clear"),
+];
+
+/// Completions for the last word of `input`: the byte offset the word
+/// starts at and every value it could become.
+pub fn complete(input: &str) -> (usize, Vec<String>) {
+    let start = input.rfind(' ').map_or(0, |space| space + 1);
+    let words: Vec<&str> = input[..start].split_whitespace().collect();
+    let partial = &input[start..];
+
+    let options: Vec<String> = match words.as_slice() {
+        [] => COMMANDS.iter().map(|(name, _)| name.to_string()).collect(),
+        ["set" | "get"] => Config::field_names(),
+        ["set", "ai_difficulty"] | ["ai"] => AiDifficulty::ALL
+            .iter()
+            .map(|difficulty| difficulty.name().to_string())
+            .collect(),
+        ["record"] => vec!["start".to_string(), "stop".to_string()],
+        ["slowmo"] => vec!["off".to_string()],
+        ["pause"] => vec!["on".to_string(), "off".to_string()],
+        ["spawn"] => vec!["ball".to_string()],
+        ["profile"] => vec!["dump".to_string(), "reset".to_string()],
+        ["leaderboard"] => vec!["export".to_string()],
+        _ => Vec::new(),
+    };
+    let matches = options
+        .into_iter()
+        .filter(|option| option.starts_with(partial))
+        .collect();
+    (start, matches)
+}
+
+/// Runs one console line against the engine and world, returning what to
+/// print.
+pub fn execute(line: &str, engine: &mut Engine, world: &mut World) -> Result<String, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let Some((&command, args)) = words.split_first() else {
+        return Ok(String::new());
+    };
+
+    match command {
+        "help" => Ok(COMMANDS
+            .iter()
+            .map(|(_, usage)| *usage)
+            .collect::<Vec<_>>()
+            .join("\n")),
+        "set" => set(engine, args),
+        "get" => match args {
+            [name] => engine
+                .config
+                .get_field(name)
+                .map(|value| format!("{} = {}", name, value))
+                .ok_or_else(|| format!("unknown config field '{}'", name)),
+            _ => Err(usage("get")),
+        },
+        "spawn" => spawn(engine, world, args),
+        "score" => match args {
+            [player, ai] => {
+                let player = parse_number(player)?;
+                let ai = parse_number(ai)?;
+                let mut score = world.resource_mut::<Score>();
+                score.player_score = player;
+                score.ai_score = ai;
+                Ok(format!("score {} - {}", player, ai))
+            }
+            _ => Err(usage("score")),
+        },
+        "ai" => match args {
+            [] => Ok(format!("ai {}", engine.config.ai_difficulty.name())),
+            [difficulty] => set(engine, &["ai_difficulty", difficulty]),
+            _ => Err(usage("ai")),
+        },
+        "slowmo" => slow_motion(engine, args),
+        "pause" => {
+            match args {
+                [] => engine.toggle_pause(),
+                ["on"] => engine.set_paused(true),
+                ["off"] => engine.set_paused(false),
+                _ => return Err(usage("pause")),
+            }
+            Ok(if engine.is_paused() {
+                "paused"
+            } else {
+                "resumed"
+            }
+            .to_string())
+        }
+        "step" => {
+            let frames = match args {
+                [] => 1,
+                [frames] => parse_number(frames)?,
+                _ => return Err(usage("step")),
+            };
+            if frames > MAX_STEP {
+                return Err(format!("step at most {} frames at a time", MAX_STEP));
+            }
+            engine.step_frames(frames);
+            Ok(format!("stepping {} frame(s)", frames))
+        }
+        "record" => record(engine, args),
+        "profile" => profile(world, args),
+        "stats" => {
+            if !world.has_resource::<Profile>() {
+                return Err("no player profile loaded".to_string());
+            }
+            let profile = world.resource::<Profile>();
+            let mut lines = vec![profile.name.clone()];
+            lines.extend(profile::screen::lines(&profile));
+            Ok(lines.join("\n"))
+        }
+        "leaderboard" => leaderboard(engine, world, args),
+        _ => Err(format!("unknown command '{}', try help", command)),
+    }
+}
+
+fn usage(command: &str) -> String {
+    let usage = COMMANDS
+        .iter()
+        .find(|(name, _)| *name == command)
+        .map_or(command, |(_, usage)| usage);
+    format!("usage: {}", usage)
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str) -> Result<T, String> {
+    text.parse()
+        .map_err(|_| format!("'{}' is not a number", text))
+}
+
+/// Changes a config field on a copy and hands it to the engine, so nothing
+/// changes if the value is bad or the field needs a restart.
+fn set(engine: &mut Engine, args: &[&str]) -> Result<String, String> {
+    let [name, value @ ..] = args else {
+        return Err(usage("set"));
+    };
+    if value.is_empty() {
+        return Err(usage("set"));
+    }
+
+    let mut config = engine.config.clone();
+    config.set_field(name, &value.join(" "))?;
+    engine.apply_config(config)?;
+    let value = engine.config.get_field(name).unwrap_or_default();
+    Ok(format!("{} = {}", name, value))
+}
+
+fn spawn(engine: &Engine, world: &mut World, args: &[&str]) -> Result<String, String> {
+    let (x, y) = match args {
+        ["ball"] => (
+            engine.config.window_width as f32 / 2.0,
+            engine.config.window_height as f32 / 2.0,
+        ),
+        ["ball", x, y] => (parse_number(x)?, parse_number(y)?),
+        _ => return Err(usage("spawn")),
+    };
+
+    let entity = world.spawn_with(Ball::new(x, y, engine.config.ball_speed));
+    world.insert(entity, ExtraBall);
+    Ok(format!("spawned ball at {:.0},{:.0}", x, y))
+}
+
+fn slow_motion(engine: &mut Engine, args: &[&str]) -> Result<String, String> {
+    let time_scale = match args {
+        [] if engine.time_scale() != 1.0 => 1.0,
+        [] => SLOW_MOTION,
+        ["off"] => 1.0,
+        [scale] => parse_number::<f32>(scale)?,
+        _ => return Err(usage("slowmo")),
+    };
+    if !time_scale.is_finite() || time_scale <= 0.0 {
+        return Err("time scale must be above 0".to_string());
+    }
+
+    engine.set_time_scale(time_scale);
+    Ok(format!("time scale {}", time_scale))
+}
+
+fn record(engine: &mut Engine, args: &[&str]) -> Result<String, String> {
+    let start = match args {
+        [] => !engine.is_recording(),
+        ["start"] => true,
+        ["stop"] => false,
+        _ => return Err(usage("record")),
+    };
+
+    if start {
+        if engine.is_recording() {
+            return Err("already recording".to_string());
+        }
+        let path = engine
+            .start_timestamped_recording()
+            .map_err(|e| e.to_string())?;
+        Ok(format!("recording to {}", path.display()))
+    } else {
+        match engine.stop_recording() {
+            Some(Ok(path)) => Ok(format!("saved recording to {}", path.display())),
+            Some(Err(e)) => Err(e.to_string()),
+            None => Err("not recording".to_string()),
+        }
+    }
+}
+
+fn profile(world: &World, args: &[&str]) -> Result<String, String> {
+    if !world.has_resource::<Profiler>() {
+        return Err("profiling is off".to_string());
+    }
+    let mut profiler = world.resource_mut::<Profiler>();
+    match args {
+        [] => Ok(profiler
+            .stats()
+            .iter()
+            .map(|stats| {
+                format!(
+                    "{} avg {:.2} max {:.2} p99 {:.2} ms",
+                    stats.name, stats.avg, stats.max, stats.p99
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")),
+        ["dump", file @ ..] if file.len() <= 1 => {
+            let path = Path::new(file.first().copied().unwrap_or(PROFILE_CSV));
+            profiler
+                .save_csv(path)
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+            Ok(format!("saved frame timings to {}", path.display()))
+        }
+        ["reset"] => {
+            profiler.reset();
+            Ok("frame timings cleared".to_string())
+        }
+        _ => Err(usage("profile")),
+    }
+}
+
+/// Lists or exports the board, ranked the way the config sets it up.
+fn leaderboard(engine: &Engine, world: &World, args: &[&str]) -> Result<String, String> {
+    if !world.has_resource::<Leaderboard>() {
+        return Err("no leaderboard loaded".to_string());
+    }
+    let board = world.resource::<Leaderboard>();
+    let config = &engine.config;
+    let (metric, difficulty) = (config.leaderboard_metric, config.leaderboard_difficulty);
+    match args {
+        [] => {
+            let ranked = board.ranked(metric, difficulty);
+            if ranked.is_empty() {
+                return Ok("the leaderboard is empty".to_string());
+            }
+            Ok(ranked
+                .iter()
+                .take(config.leaderboard_size)
+                .enumerate()
+                .map(|(index, entry)| {
+                    format!(
+                        "{}. {} {} ({} - {}, {})",
+                        index + 1,
+                        entry.initials,
+                        metric.format(entry),
+                        entry.player_score,
+                        entry.ai_score,
+                        entry.difficulty.name()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        ["export", file @ ..] if file.len() <= 1 => {
+            let path = Path::new(file.first().copied().unwrap_or(LEADERBOARD_EXPORT));
+            board
+                .save_export(path, metric, difficulty, config.leaderboard_size)
+                .map_err(|e| e.to_string())?;
+            Ok(format!("exported the leaderboard to {}", path.display()))
+        }
+        _ => Err(usage("leaderboard")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (Engine, World) {
+        let engine = Engine::headless(Config::default()).unwrap();
+        let mut world = World::new();
+        world.register::<Ball>();
+        world.register::<ExtraBall>();
+        world.insert_resource(Score::new());
+        (engine, world)
+    }
+
+    #[test]
+    fn sets_and_gets_config() {
+        let (mut engine, mut world) = setup();
+        let result = execute("set paddle_speed 400", &mut engine, &mut world);
+        assert_eq!(result.as_deref(), Ok("paddle_speed = 400.0"));
+        assert_eq!(engine.config.paddle_speed, 400.0);
+
+        assert!(execute("set window_width 1024", &mut engine, &mut world).is_err());
+        assert!(execute("set paddle_speed fast", &mut engine, &mut world).is_err());
+        assert_eq!(engine.config.window_width, 800);
+        assert_eq!(engine.config.paddle_speed, 400.0);
+
+        execute("ai hard", &mut engine, &mut world).unwrap();
+        assert_eq!(engine.config.ai_difficulty, AiDifficulty::Hard);
+        let result = execute("get ai_difficulty", &mut engine, &mut world);
+        assert_eq!(result.as_deref(), Ok("ai_difficulty = \"hard\""));
+    }
+
+    #[test]
+    fn changes_the_game() {
+        let (mut engine, mut world) = setup();
+        execute("spawn ball", &mut engine, &mut world).unwrap();
+        execute("spawn ball 100 200", &mut engine, &mut world).unwrap();
+        assert_eq!(world.components::<ExtraBall>().len(), 2);
+        assert!(
+            world
+                .components::<Ball>()
+                .iter()
+                .any(|(_, ball)| (ball.x, ball.y) == (100.0, 200.0))
+        );
+
+        execute("score 3 7", &mut engine, &mut world).unwrap();
+        let score = world.resource::<Score>().clone();
+        assert_eq!((score.player_score, score.ai_score), (3, 7));
+
+        execute("slowmo", &mut engine, &mut world).unwrap();
+        assert_eq!(engine.time_scale(), SLOW_MOTION);
+        execute("slowmo", &mut engine, &mut world).unwrap();
+        assert_eq!(engine.time_scale(), 1.0);
+        assert!(execute("slowmo 0", &mut engine, &mut world).is_err());
+
+        assert!(execute("step 3601", &mut engine, &mut world).is_err());
+        execute("step 2", &mut engine, &mut world).unwrap();
+        assert!(engine.is_paused());
+        execute("pause", &mut engine, &mut world).unwrap();
+        assert!(!engine.is_paused());
+
+        assert!(execute("profile", &mut engine, &mut world).is_err());
+        world.insert_resource(Profiler::new());
+        execute("profile reset", &mut engine, &mut world).unwrap();
+
+        assert!(execute("stats", &mut engine, &mut world).is_err());
+        world.insert_resource(Profile::new("Ann"));
+        let stats = execute("stats", &mut engine, &mut world).unwrap();
+        assert!(stats.starts_with("Ann\nMATCHES 0"));
+
+        assert!(execute("leaderboard", &mut engine, &mut world).is_err());
+        world.insert_resource(Leaderboard::new());
+        let result = execute("leaderboard", &mut engine, &mut world);
+        assert_eq!(result.as_deref(), Ok("the leaderboard is empty"));
+
+        assert!(execute("record stop", &mut engine, &mut world).is_err());
+        assert!(execute("launch", &mut engine, &mut world).is_err());
+    }
+}
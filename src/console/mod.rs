@@ -0,0 +1,317 @@
+// src/console/mod.rs
+pub mod commands;
+
+use std::collections::VecDeque;
+
+use minifb::Key;
+
+use crate::ecs::World;
+use crate::debug;
+use crate::engine::Engine;
+use crate::engine::font::{ADVANCE, LINE_HEIGHT};
+use crate::engine::framebuffer::{Framebuffer, opaque, with_alpha};
+
+const MAX_OUTPUT: usize = 200; // Lines kept for scrollback
+const MAX_HISTORY: usize = 100;
+const HEIGHT_FRACTION: f32 = 0.45; // Share of the screen the open console covers
+const MARGIN: i32 = 6;
+
+const TEXT: u32 = opaque(0xE0E0E0);
+const INPUT: u32 = opaque(0xFFFF80);
+const EDGE: u32 = opaque(0x808080);
+
+/// Drop-down developer console, toggled with the backtick key. Lines typed
+/// into it run as `commands`, with command history on Up/Down and
+/// completion on Tab.
+pub struct Console {
+    open: bool,
+    input: String,
+    history: Vec<String>,
+    history_index: Option<usize>, // Entry shown while browsing history
+    output: VecDeque<String>,
+}
+
+#[allow(dead_code)]
+impl Console {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            input: String::new(),
+            history: Vec::new(),
+            history_index: None,
+            output: VecDeque::new(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    pub fn output(&self) -> impl Iterator<Item = &str> {
+        self.output.iter().map(String::as_str)
+    }
+
+    /// Adds typed characters to the input line. The backtick that toggles
+    /// the console is left out.
+    pub fn type_text(&mut self, text: &str) {
+        self.input
+            .extend(text.chars().filter(|&c| c != '`' && !c.is_control()));
+    }
+
+    pub fn backspace(&mut self) {
+        self.input.pop();
+    }
+
+    /// Replaces the input with the previous history entry.
+    pub fn history_prev(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match self.history_index {
+            Some(index) => index.saturating_sub(1),
+            None => self.history.len() - 1,
+        };
+        self.history_index = Some(index);
+        self.input = self.history[index].clone();
+    }
+
+    /// Replaces the input with the next history entry, or clears it after
+    /// the newest one.
+    pub fn history_next(&mut self) {
+        let Some(index) = self.history_index else {
+            return;
+        };
+        if index + 1 < self.history.len() {
+            self.history_index = Some(index + 1);
+            self.input = self.history[index + 1].clone();
+        } else {
+            self.history_index = None;
+            self.input.clear();
+        }
+    }
+
+    /// Completes the word being typed. A single match is filled in; several
+    /// extend the input to their common prefix and get listed.
+    pub fn complete(&mut self) {
+        let (start, candidates) = commands::complete(&self.input);
+        match candidates.as_slice() {
+            [] => {}
+            [only] => {
+                self.input.truncate(start);
+                self.input.push_str(only);
+                self.input.push(' ');
+            }
+            [first, rest @ ..] => {
+                let common = rest.iter().fold(first.len(), |length, candidate| {
+                    first
+                        .bytes()
+                        .zip(candidate.bytes())
+                        .take(length)
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                });
+                self.input.truncate(start);
+                self.input.push_str(&first[..common]);
+                self.print(candidates.join("  "));
+            }
+        }
+    }
+
+    /// Takes the input line, echoing it and adding it to the history.
+    /// Returns `None` for a blank line.
+    pub fn submit(&mut self) -> Option<String> {
+        let line = std::mem::take(&mut self.input);
+        self.history_index = None;
+        let line = line.trim().to_string();
+        if line.is_empty() {
+            return None;
+        }
+
+        self.print(format!("> {}", line));
+        if self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+            if self.history.len() > MAX_HISTORY {
+                self.history.remove(0);
+            }
+        }
+        Some(line)
+    }
+
+    /// Adds output, one entry per line.
+    pub fn print(&mut self, text: impl AsRef<str>) {
+        for line in text.as_ref().lines() {
+            if self.output.len() == MAX_OUTPUT {
+                self.output.pop_front();
+            }
+            self.output.push_back(line.to_string());
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.output.clear();
+    }
+
+    /// Runs a line the way Enter does, printing the result.
+    pub fn run(&mut self, line: &str, engine: &mut Engine, world: &mut World) {
+        debug!("console command"; line = line);
+        if line == "clear" {
+            self.clear();
+            return;
+        }
+        match commands::execute(line, engine, world) {
+            Ok(message) => self.print(message),
+            Err(message) => {
+                debug!("console command failed"; error = message);
+                self.print(format!("error: {}", message));
+            }
+        }
+    }
+
+    /// Applies this frame's typing and editing keys. Call after
+    /// `Engine::present`, when key state is current.
+    pub fn update(&mut self, engine: &mut Engine, world: &mut World) {
+        let text = engine.typed_text();
+        if !self.open {
+            return;
+        }
+
+        self.type_text(&text);
+        if engine.key_repeated(Key::Backspace) {
+            self.backspace();
+        }
+        if engine.key_repeated(Key::Up) {
+            self.history_prev();
+        }
+        if engine.key_repeated(Key::Down) {
+            self.history_next();
+        }
+        if engine.key_pressed(Key::Tab) {
+            self.complete();
+        }
+        if engine.key_pressed(Key::Enter)
+            && let Some(line) = self.submit()
+        {
+            self.run(&line, engine, world);
+        }
+    }
+
+    /// Draws the console over the top of the frame, newest output just
+    /// above the input line.
+    pub fn draw(&self, frame: &mut Framebuffer) {
+        if !self.open {
+            return;
+        }
+
+        let width = frame.width() as i32;
+        let height = (frame.height() as f32 * HEIGHT_FRACTION) as i32;
+        let line_height = LINE_HEIGHT as i32;
+        frame.fill_rect(0, 0, width, height, with_alpha(0x101018, 0.85));
+        frame.line(0, height, width - 1, height, EDGE);
+
+        // Keep the end of a long input line in view
+        let columns = ((width - MARGIN * 2) / ADVANCE as i32).max(1) as usize;
+        let prompt = format!("> {}_", self.input);
+        let skip = prompt.chars().count().saturating_sub(columns);
+        let prompt: String = prompt.chars().skip(skip).collect();
+        let input_y = height - MARGIN - line_height;
+        frame.text(&prompt, MARGIN, input_y, 1, INPUT);
+
+        let mut y = input_y - line_height;
+        for line in self.output.iter().rev() {
+            if y < MARGIN {
+                break;
+            }
+            frame.text(line, MARGIN, y, 1, TEXT);
+            y -= line_height;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submit(console: &mut Console, line: &str) {
+        console.type_text(line);
+        console.submit();
+    }
+
+    #[test]
+    fn edits_and_submits_lines() {
+        let mut console = Console::new();
+        console.type_text("`set paddle_speed 40x");
+        console.backspace();
+        console.type_text("0");
+        assert_eq!(console.input(), "set paddle_speed 400");
+
+        assert_eq!(console.submit().as_deref(), Some("set paddle_speed 400"));
+        assert_eq!(console.input(), "");
+        assert_eq!(console.output().last(), Some("> set paddle_speed 400"));
+        assert_eq!(console.submit(), None);
+    }
+
+    #[test]
+    fn browses_history() {
+        let mut console = Console::new();
+        submit(&mut console, "help");
+        submit(&mut console, "ai hard");
+        submit(&mut console, "ai hard");
+
+        console.history_prev();
+        assert_eq!(console.input(), "ai hard");
+        console.history_prev();
+        assert_eq!(console.input(), "help");
+        console.history_prev();
+        assert_eq!(console.input(), "help");
+        console.history_next();
+        assert_eq!(console.input(), "ai hard");
+        console.history_next();
+        assert_eq!(console.input(), "");
+    }
+
+    #[test]
+    fn completes_commands_and_fields() {
+        let mut console = Console::new();
+        console.type_text("sl");
+        console.complete();
+        assert_eq!(console.input(), "slowmo ");
+
+        console = Console::new();
+        console.type_text("set ball_sp");
+        console.complete();
+        assert_eq!(console.input(), "set ball_sp");
+        assert!(console.output().any(|line| line.contains("ball_speed_up")));
+
+        console = Console::new();
+        console.type_text("set magnus");
+        console.complete();
+        assert_eq!(console.input(), "set magnus_coefficient ");
+
+        console = Console::new();
+        console.type_text("ai h");
+        console.complete();
+        assert_eq!(console.input(), "ai hard ");
+    }
+
+    #[test]
+    fn draws_only_when_open() {
+        let mut console = Console::new();
+        let mut frame = Framebuffer::new(200, 100);
+        console.draw(&mut frame);
+        assert!(frame.pixels().iter().all(|&pixel| pixel == 0));
+
+        console.toggle();
+        console.print("hello");
+        console.draw(&mut frame);
+        assert!(frame.pixels().contains(&(TEXT & 0xFFFFFF)));
+        assert!(frame.pixels().contains(&(INPUT & 0xFFFFFF)));
+    }
+}
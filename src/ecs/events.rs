@@ -0,0 +1,29 @@
+// src/ecs/events.rs
+/// Queue of events sent by systems during a frame. Every consumer sees all
+/// events of the frame; the queue is cleared at the start of the next one.
+pub struct Events<T> {
+    events: Vec<T>,
+}
+
+#[allow(dead_code)]
+impl<T> Events<T> {
+    pub fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    pub fn send(&mut self, event: T) {
+        self.events.push(event);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.events.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
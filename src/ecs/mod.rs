@@ -0,0 +1,8 @@
+// src/ecs/mod.rs
+pub mod events;
+pub mod system;
+pub mod world;
+
+pub use events::Events;
+pub use system::{Schedule, Stage, System};
+pub use world::{ComponentStorage, World};
@@ -0,0 +1,64 @@
+// src/ecs/system.rs
+use std::time::Instant;
+
+use crate::ecs::World;
+use crate::engine::Config;
+use crate::engine::profiler::Profiler;
+
+/// Where a system runs in the frame. Stages run in declaration order; systems
+/// within a stage run in the order they were added.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    PreUpdate,
+    Movement,
+    Collision,
+    Scoring,
+    PostUpdate,
+}
+
+pub trait System {
+    fn name(&self) -> &'static str;
+    fn stage(&self) -> Stage;
+    fn run(&mut self, world: &mut World, config: &Config, delta_time: f32);
+}
+
+pub struct Schedule {
+    systems: Vec<Box<dyn System>>,
+}
+
+#[allow(dead_code)]
+impl Schedule {
+    pub fn new() -> Self {
+        Self {
+            systems: Vec::new(),
+        }
+    }
+
+    pub fn add_system<S: System + 'static>(&mut self, system: S) -> &mut Self {
+        // Insert after the last system of the same or an earlier stage to keep
+        // the order stable within a stage.
+        let stage = system.stage();
+        let position = self
+            .systems
+            .iter()
+            .position(|s| s.stage() > stage)
+            .unwrap_or(self.systems.len());
+        self.systems.insert(position, Box::new(system));
+        self
+    }
+
+    pub fn system_names(&self) -> Vec<&'static str> {
+        self.systems.iter().map(|s| s.name()).collect()
+    }
+
+    /// Runs every system, timing each into the world's `Profiler` if it
+    /// has one.
+    pub fn run(&mut self, world: &mut World, config: &Config, delta_time: f32) {
+        for system in self.systems.iter_mut() {
+            let start = Instant::now();
+            system.run(world, config, delta_time);
+            Profiler::record_in(world, system.name(), start.elapsed());
+        }
+    }
+}
@@ -0,0 +1,319 @@
+// src/ecs/world.rs
+use std::any::{Any, TypeId};
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
+
+/// Handle to an entity. The generation makes stale handles to a despawned
+/// entity stop matching once its slot is reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+}
+
+/// Dense-by-index storage for one component type.
+pub struct ComponentStorage<T> {
+    slots: Vec<Option<(Entity, T)>>,
+}
+
+#[allow(dead_code)]
+impl<T> ComponentStorage<T> {
+    fn new() -> Self {
+        Self { slots: Vec::new() }
+    }
+
+    fn insert(&mut self, entity: Entity, component: T) -> Option<T> {
+        let index = entity.index();
+        if index >= self.slots.len() {
+            self.slots.resize_with(index + 1, || None);
+        }
+        self.slots[index]
+            .replace((entity, component))
+            .map(|(_, old)| old)
+    }
+
+    fn remove(&mut self, entity: Entity) -> Option<T> {
+        match self.slots.get(entity.index()) {
+            Some(Some((owner, _))) if *owner == entity => {
+                self.slots[entity.index()].take().map(|(_, c)| c)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        match self.slots.get(entity.index()) {
+            Some(Some((owner, component))) if *owner == entity => Some(component),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        match self.slots.get_mut(entity.index()) {
+            Some(Some((owner, component))) if *owner == entity => Some(component),
+            _ => None,
+        }
+    }
+
+    /// Mutably borrows the components of two different entities at once.
+    pub fn pair_mut(&mut self, first: Entity, second: Entity) -> Option<(&mut T, &mut T)> {
+        if first.index() == second.index() || !self.contains(first) || !self.contains(second) {
+            return None;
+        }
+        let (low, high) = (first.index().min(second.index()), first.index().max(second.index()));
+        let (head, tail) = self.slots.split_at_mut(high);
+        let low_component = &mut head[low].as_mut()?.1;
+        let high_component = &mut tail[0].as_mut()?.1;
+        if first.index() < second.index() {
+            Some((low_component, high_component))
+        } else {
+            Some((high_component, low_component))
+        }
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.get(entity).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(|slot| slot.is_none())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.slots
+            .iter()
+            .filter_map(|slot| slot.as_ref().map(|(e, c)| (*e, c)))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        self.slots
+            .iter_mut()
+            .filter_map(|slot| slot.as_mut().map(|(e, c)| (*e, c)))
+    }
+
+    pub fn entities(&self) -> Vec<Entity> {
+        self.iter().map(|(e, _)| e).collect()
+    }
+
+    /// Pairs each component with the same entity's component in `other`,
+    /// skipping entities that don't have both.
+    pub fn join_mut<'a, U>(
+        &'a mut self,
+        other: &'a ComponentStorage<U>,
+    ) -> impl Iterator<Item = (Entity, &'a mut T, &'a U)> {
+        self.iter_mut()
+            .filter_map(|(e, c)| other.get(e).map(|o| (e, c, o)))
+    }
+}
+
+// Type-erased view of a storage so despawn can clear every component type.
+trait AnyStorage {
+    fn remove_entity(&mut self, entity: Entity);
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: 'static> AnyStorage for RefCell<ComponentStorage<T>> {
+    fn remove_entity(&mut self, entity: Entity) {
+        self.get_mut().remove(entity);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Owns every entity, its components and the global resources.
+///
+/// Storages and resources live in `RefCell`s so a system can read one
+/// component type while mutating another, e.g. iterate paddles mutably
+/// while looking at the balls.
+pub struct World {
+    generations: Vec<u32>,
+    alive: Vec<bool>,
+    free: Vec<u32>,
+    storages: HashMap<TypeId, Box<dyn AnyStorage>>,
+    resources: HashMap<TypeId, Box<dyn Any>>,
+}
+
+#[allow(dead_code)]
+impl World {
+    pub fn new() -> Self {
+        Self {
+            generations: Vec::new(),
+            alive: Vec::new(),
+            free: Vec::new(),
+            storages: HashMap::new(),
+            resources: HashMap::new(),
+        }
+    }
+
+    pub fn spawn(&mut self) -> Entity {
+        if let Some(index) = self.free.pop() {
+            let slot = index as usize;
+            self.generations[slot] += 1;
+            self.alive[slot] = true;
+            Entity {
+                index,
+                generation: self.generations[slot],
+            }
+        } else {
+            let index = self.generations.len() as u32;
+            self.generations.push(0);
+            self.alive.push(true);
+            Entity {
+                index,
+                generation: 0,
+            }
+        }
+    }
+
+    /// Spawns an entity carrying a single component.
+    pub fn spawn_with<T: 'static>(&mut self, component: T) -> Entity {
+        let entity = self.spawn();
+        self.insert(entity, component);
+        entity
+    }
+
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+        for storage in self.storages.values_mut() {
+            storage.remove_entity(entity);
+        }
+        self.alive[entity.index()] = false;
+        self.free.push(entity.index);
+        true
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        let index = entity.index();
+        index < self.alive.len()
+            && self.alive[index]
+            && self.generations[index] == entity.generation
+    }
+
+    /// Creates the storage for `T` up front so queries work before any
+    /// entity carries the component.
+    pub fn register<T: 'static>(&mut self) {
+        self.storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(RefCell::new(ComponentStorage::<T>::new())));
+    }
+
+    pub fn insert<T: 'static>(&mut self, entity: Entity, component: T) -> Option<T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        self.register::<T>();
+        self.components_mut::<T>().insert(entity, component)
+    }
+
+    pub fn remove<T: 'static>(&mut self, entity: Entity) -> Option<T> {
+        self.storage::<T>()?.borrow_mut().remove(entity)
+    }
+
+    /// Borrows every `T` in the world. Panics if `T` was never registered.
+    pub fn components<T: 'static>(&self) -> Ref<'_, ComponentStorage<T>> {
+        self.storage::<T>()
+            .unwrap_or_else(|| panic!("component {} not registered", std::any::type_name::<T>()))
+            .borrow()
+    }
+
+    /// Mutably borrows every `T` in the world. Panics if `T` was never registered.
+    pub fn components_mut<T: 'static>(&self) -> RefMut<'_, ComponentStorage<T>> {
+        self.storage::<T>()
+            .unwrap_or_else(|| panic!("component {} not registered", std::any::type_name::<T>()))
+            .borrow_mut()
+    }
+
+    /// Calls `f` for every entity with both an `A` and a `B`, the `A`
+    /// borrowed mutably. Panics if either was never registered, or if they
+    /// are the same type.
+    pub fn query2<A: 'static, B: 'static>(&self, mut f: impl FnMut(Entity, &mut A, &B)) {
+        assert!(
+            TypeId::of::<A>() != TypeId::of::<B>(),
+            "query2 needs two different component types, got {} twice",
+            std::any::type_name::<A>()
+        );
+        let others = self.components::<B>();
+        for (entity, a, b) in self.components_mut::<A>().join_mut(&others) {
+            f(entity, a, b);
+        }
+    }
+
+    fn storage<T: 'static>(&self) -> Option<&RefCell<ComponentStorage<T>>> {
+        self.storages
+            .get(&TypeId::of::<T>())
+            .and_then(|s| s.as_any().downcast_ref::<RefCell<ComponentStorage<T>>>())
+    }
+
+    pub fn insert_resource<T: 'static>(&mut self, resource: T) {
+        self.resources
+            .insert(TypeId::of::<T>(), Box::new(RefCell::new(resource)));
+    }
+
+    pub fn has_resource<T: 'static>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Borrows a resource. Panics if it was never inserted.
+    pub fn resource<T: 'static>(&self) -> Ref<'_, T> {
+        self.resource_cell::<T>().borrow()
+    }
+
+    /// Mutably borrows a resource. Panics if it was never inserted.
+    pub fn resource_mut<T: 'static>(&self) -> RefMut<'_, T> {
+        self.resource_cell::<T>().borrow_mut()
+    }
+
+    fn resource_cell<T: 'static>(&self) -> &RefCell<T> {
+        self.resources
+            .get(&TypeId::of::<T>())
+            .and_then(|r| r.downcast_ref::<RefCell<T>>())
+            .unwrap_or_else(|| panic!("resource {} not inserted", std::any::type_name::<T>()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query2_visits_entities_with_both_components() {
+        let mut world = World::new();
+        world.register::<u32>();
+        world.register::<&'static str>();
+        let both = world.spawn_with(1u32);
+        world.insert(both, "both");
+        world.spawn_with(2u32);
+        let only_name = world.spawn_with("name");
+
+        let mut seen = Vec::new();
+        world.query2::<u32, &'static str>(|entity, number, name| {
+            *number += 10;
+            seen.push((entity, *name));
+        });
+        assert_eq!(seen, [(both, "both")]);
+        assert_eq!(world.components::<u32>().get(both), Some(&11));
+        assert!(!world.components::<u32>().contains(only_name));
+    }
+
+    #[test]
+    #[should_panic(expected = "two different component types")]
+    fn query2_rejects_one_type_twice() {
+        let mut world = World::new();
+        world.register::<u32>();
+        world.query2::<u32, u32>(|_, _, _| {});
+    }
+}
@@ -1,21 +1,67 @@
 
 // engine/config.rs
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::fs::atomic_write;
+use crate::engine::viewport::ScaleMode;
+use crate::leaderboard::Metric;
+use crate::log::Level;
+
+/// How hard the computer paddle plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AiDifficulty {
+    Easy,   // Slow, follows the ball
+    Normal, // Follows the ball
+    Hard,   // Fast, moves to where the ball will arrive
+}
+
+impl AiDifficulty {
+    pub const ALL: [AiDifficulty; 3] = [AiDifficulty::Easy, AiDifficulty::Normal, AiDifficulty::Hard];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AiDifficulty::Easy => "easy",
+            AiDifficulty::Normal => "normal",
+            AiDifficulty::Hard => "hard",
+        }
+    }
+
+    pub fn paddle_speed(self) -> f32 {
+        match self {
+            AiDifficulty::Easy => 150.0,
+            AiDifficulty::Normal => 200.0,
+            AiDifficulty::Hard => 320.0,
+        }
+    }
+}
 
 #[allow(dead_code)]
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
-    pub window_width: u32,
+    pub window_width: u32,  // Logical resolution the game is drawn at, and the initial window size
     pub window_height: u32,
+    pub scale_mode: ScaleMode, // How the logical frame fills a resized window
+    pub fullscreen: bool,
+    pub fullscreen_size: Option<[u32; 2]>, // Fullscreen window size, null to use the monitor's
     pub window_title: String,
     pub paddle_speed: f32,
-    pub ball_speed: f32,
-    pub ball_radius: f32,
+    pub ball_speed: f32, // Serve speed, restored on every serve
+    pub ball_speed_up: f32, // Speed multiplier applied on every paddle hit
+    pub ball_max_speed: f32,
+    pub ball_max_angle: f32, // Steepest allowed angle from horizontal, in degrees
+    pub ball_radius: f32, // The ball is a circle, used for both collision and rendering
+    pub ball_antialias: bool,
     pub paddle_width: f32,
     pub paddle_height: f32,
     pub ball_color: [f32; 4], // RGBA format
     pub paddle_color: [f32; 4], // RGBA format
-    pub ball_size: f32,
     pub fps_target: u64,
+    pub max_delta_time: f32, // Longest frame the simulation sees, in seconds; longer stalls are cut short
     pub background_color: [f32; 4], // RGBA format
     pub font_path: String,
     pub font_size: u32,
@@ -28,6 +74,48 @@ pub struct Config {
     pub score_font_outline_color: [f32; 4], // RGBA format
     pub score_font_outline_thickness: f32,
     pub score_font_outline_offset: [f32; 2], // [x, y] offset for outline
+    pub ball_collisions: bool, // Balls bounce off each other
+    pub multiball_enabled: bool,
+    pub multiball_rally_hits: u32, // Paddle hits per rally before each extra ball
+    pub multiball_max_balls: u32,
+    pub spin_transfer: f32, // Spin gained per unit of paddle velocity on a hit
+    pub paddle_velocity_transfer: f32, // Fraction of paddle velocity added to the ball
+    pub magnus_coefficient: f32, // Sideways acceleration per unit of spin and speed
+    pub spin_decay: f32, // Fraction of spin lost per second in flight
+    pub wall_spin_friction: f32, // Tangential speed gained per unit of spin on a wall bounce
+    pub spin_bounce_retention: f32, // Fraction of spin kept after a wall bounce
+    pub max_spin: f32,
+    pub winning_score: u32,
+    pub ai_difficulty: AiDifficulty,
+    pub audio_volume: f32, // 0.0 to 1.0
+    pub audio_muted: bool,
+    pub particles_enabled: bool,
+    pub ball_trail_enabled: bool,
+    pub ball_trail_length: usize, // Positions kept per ball
+    pub screen_shake_enabled: bool,
+    pub screen_shake_intensity: f32, // Maximum offset in pixels
+    pub asset_dir: String,
+    pub sprite_atlas: Option<String>, // Atlas file in asset_dir, see assets::TextureAtlas
+    pub paddle_sprite: Option<String>, // Atlas region or PNG in asset_dir, stretched to the paddle
+    pub ball_sprite: Option<String>,
+    pub ball_sprite_frames: usize, // Frames in a horizontal ball strip, cycled as it spins
+    pub debug_overlay: bool, // Start with the F3 debug overlay shown
+    pub screenshot_dir: String, // Where F12 saves screenshots
+    pub recording_dir: String,  // Where F9 saves GIF recordings
+    pub record_frame_skip: u32, // Frames dropped between recorded frames
+    pub record_scale: f32,      // Recorded size relative to the window
+    pub log_level: Level,       // error, warn, info, debug or trace
+    pub log_stderr: bool,
+    pub log_file: Option<String>, // Also log to this file, rotated by size
+    pub log_file_max_bytes: u64,
+    pub log_file_count: usize, // Rotated files kept besides the current one
+    pub profile_name: String, // Player whose stats are recorded
+    pub profile_dir: String,  // Where player profiles are saved
+    pub leaderboard_file: String,
+    pub leaderboard_metric: Metric, // margin or longest_rally
+    pub leaderboard_difficulty: Option<AiDifficulty>, // Only rank matches against this AI
+    pub leaderboard_size: usize, // Places on the board
+    pub achievements_file: Option<String>, // Achievement definitions, the built-in ones if unset
 }
 
 impl Default for Config {
@@ -35,10 +123,17 @@ impl Default for Config {
         Self {
             window_width: 800,
             window_height: 600,
+            scale_mode: ScaleMode::Letterbox,
+            fullscreen: false,
+            fullscreen_size: None,
             window_title: "Ping Pong".to_string(),
             paddle_speed: 300.0,
-            ball_speed: 200.0,
-            ball_radius: 10.0,
+            ball_speed: 220.0,
+            ball_speed_up: 1.05,
+            ball_max_speed: 700.0,
+            ball_max_angle: 60.0,
+            ball_radius: 5.0,
+            ball_antialias: true,
             paddle_width: 20.0,
             paddle_height: 100.0,
             ball_color: [1.0, 1.0, 1.0, 1.0], // White
@@ -46,7 +141,7 @@ impl Default for Config {
             background_color: [0.0, 0.0, 0.0, 1.0], // Black
             font_path: "assets/fonts/Roboto-Regular.ttf".to_string(),
             font_size: 48,
-            score_color: [1.0, 1.0, 1.0, 1.0], // White
+            score_color: [0.0, 1.0, 1.0, 1.0], // Cyan
             score_position: [400.0, 50.0], // Center top
             score_font_size: 48,
             score_spacing: 10.0,
@@ -55,8 +150,360 @@ impl Default for Config {
             score_font_outline_color: [0.0, 0.0, 0.0, 1.0], // Black
             score_font_outline_thickness: 2.0,
             score_font_outline_offset: [1.0, 1.0],
-            ball_size: 10.0,
             fps_target: 60,
+            max_delta_time: 0.1,
+            ball_collisions: false,
+            multiball_enabled: false,
+            multiball_rally_hits: 10,
+            multiball_max_balls: 3,
+            spin_transfer: 0.02,
+            paddle_velocity_transfer: 0.25,
+            magnus_coefficient: 0.05,
+            spin_decay: 0.5,
+            wall_spin_friction: 5.0,
+            spin_bounce_retention: 0.6,
+            max_spin: 20.0,
+            winning_score: 10,
+            ai_difficulty: AiDifficulty::Normal,
+            audio_volume: 0.5,
+            audio_muted: false,
+            particles_enabled: true,
+            ball_trail_enabled: true,
+            ball_trail_length: 12,
+            screen_shake_enabled: true,
+            screen_shake_intensity: 8.0,
+            asset_dir: "assets".to_string(),
+            sprite_atlas: None,
+            paddle_sprite: None,
+            ball_sprite: None,
+            ball_sprite_frames: 1,
+            debug_overlay: false,
+            screenshot_dir: "screenshots".to_string(),
+            recording_dir: "recordings".to_string(),
+            record_frame_skip: 1,
+            record_scale: 0.5,
+            log_level: Level::Info,
+            log_stderr: true,
+            log_file: None,
+            log_file_max_bytes: 1024 * 1024,
+            log_file_count: 3,
+            profile_name: "Player".to_string(),
+            profile_dir: "profiles".to_string(),
+            leaderboard_file: "leaderboard.json".to_string(),
+            leaderboard_metric: Metric::Margin,
+            leaderboard_difficulty: None,
+            leaderboard_size: 10,
+            achievements_file: None,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl Config {
+    /// Fields that size or name the window or pick the files the player's
+    /// profile, the leaderboard and the achievements are read from, fixed
+    /// once the engine is running.
+    pub const RESTART_FIELDS: [&'static str; 8] = [
+        "window_width",
+        "window_height",
+        "window_title",
+        "fullscreen_size",
+        "profile_name",
+        "profile_dir",
+        "leaderboard_file",
+        "achievements_file",
+    ];
+
+    /// Reads a JSON config file. Fields it leaves out keep their defaults;
+    /// unknown fields are an error so typos don't go unnoticed.
+    pub fn load(path: impl AsRef<Path>) -> Result<Config, ConfigError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| ConfigError::Io(path.into(), e))?;
+        let config: Config =
+            serde_json::from_str(&text).map_err(|e| ConfigError::Parse(path.into(), e))?;
+        config
+            .validate()
+            .map_err(|e| ConfigError::Invalid(format!("{}: {}", path.display(), e)))?;
+        Ok(config)
+    }
+
+    /// Writes every field to a JSON config file. The file is written next
+    /// to the old one and renamed over it, so a crash can't leave half a
+    /// config behind.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| ConfigError::Serialize(path.into(), e))?;
+        atomic_write(path, json.as_bytes()).map_err(|e| ConfigError::Io(path.into(), e))
+    }
+
+    /// Checks values the engine can't run with, like a zero frame rate.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.window_width == 0 || self.window_height == 0 {
+            return Err(format!(
+                "window size {}x{} must not be zero",
+                self.window_width, self.window_height
+            ));
+        }
+        if self.fullscreen_size.is_some_and(|size| size.contains(&0)) {
+            return Err("fullscreen_size must not be zero".to_string());
+        }
+        if self.fps_target == 0 {
+            return Err("fps_target must be above 0".to_string());
+        }
+        let positive = [
+            ("ball_speed", self.ball_speed),
+            ("ball_speed_up", self.ball_speed_up),
+            ("ball_max_speed", self.ball_max_speed),
+            ("ball_radius", self.ball_radius),
+            ("paddle_width", self.paddle_width),
+            ("paddle_height", self.paddle_height),
+            ("max_spin", self.max_spin),
+            ("max_delta_time", self.max_delta_time),
+            ("record_scale", self.record_scale),
+        ];
+        for (name, value) in positive {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(format!("{} must be above 0", name));
+            }
+        }
+        let non_negative = [
+            ("paddle_speed", self.paddle_speed),
+            ("spin_decay", self.spin_decay),
+            ("screen_shake_intensity", self.screen_shake_intensity),
+        ];
+        for (name, value) in non_negative {
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!("{} must not be negative", name));
+            }
+        }
+        let fractions = [
+            ("spin_bounce_retention", self.spin_bounce_retention),
+            ("audio_volume", self.audio_volume),
+        ];
+        for (name, value) in fractions {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(format!("{} must be between 0 and 1", name));
+            }
+        }
+        let finite = [
+            ("spin_transfer", self.spin_transfer),
+            ("paddle_velocity_transfer", self.paddle_velocity_transfer),
+            ("magnus_coefficient", self.magnus_coefficient),
+            ("wall_spin_friction", self.wall_spin_friction),
+        ];
+        if let Some((name, _)) = finite.iter().find(|(_, value)| !value.is_finite()) {
+            return Err(format!("{} must be a number", name));
+        }
+        if self.ball_max_speed < self.ball_speed {
+            return Err("ball_max_speed must be at least ball_speed".to_string());
+        }
+        if !(self.ball_max_angle > 0.0 && self.ball_max_angle < 90.0) {
+            return Err("ball_max_angle must be between 0 and 90 degrees".to_string());
+        }
+        if self.paddle_height > self.window_height as f32 {
+            return Err("paddle_height must fit in window_height".to_string());
+        }
+        if self.winning_score == 0 {
+            return Err("winning_score must be above 0".to_string());
+        }
+        if self.multiball_max_balls == 0 {
+            return Err("multiball_max_balls must be above 0".to_string());
+        }
+        if self.leaderboard_size == 0 {
+            return Err("leaderboard_size must be above 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// Puts back any `RESTART_FIELDS` that differ from `running`, returning
+    /// their names, so the rest of the config can be applied live. Checks
+    /// across fields can fail afterwards (a paddle taller than the running
+    /// window), so validate the result again before using it.
+    pub fn keep_restart_fields(&mut self, running: &Config) -> Vec<&'static str> {
+        let mut kept = Vec::new();
+        for name in Self::RESTART_FIELDS {
+            let value = running.get_field(name);
+            if self.get_field(name) != value {
+                if let Some(value) = value {
+                    self.set_field_unchecked(name, &value)
+                        .expect("running config values parse");
+                }
+                kept.push(name);
+            }
+        }
+        kept
+    }
+
+    /// Every field name, sorted.
+    pub fn field_names() -> Vec<String> {
+        match serde_json::to_value(Config::default()) {
+            Ok(serde_json::Value::Object(fields)) => fields.keys().cloned().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The value of a field as JSON text, e.g. `300.0` or `"assets"`.
+    pub fn get_field(&self, name: &str) -> Option<String> {
+        let value = serde_json::to_value(self).ok()?;
+        value.get(name).map(|field| field.to_string())
+    }
+
+    /// Sets a field by name from text. The text is read as JSON (numbers,
+    /// `true`, `[1.0, 0.0, 0.0, 1.0]`, `null`) and otherwise taken as a
+    /// plain string, so `set ai_difficulty hard` works unquoted.
+    pub fn set_field(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let mut config = self.clone();
+        config.set_field_unchecked(name, value)?;
+        config.validate()?;
+        *self = config;
+        Ok(())
+    }
+
+    /// `set_field` without `validate`.
+    fn set_field_unchecked(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let mut fields = serde_json::to_value(&*self).map_err(|e| e.to_string())?;
+        let Some(field) = fields.get_mut(name) else {
+            return Err(format!("unknown config field '{}'", name));
+        };
+        *field = serde_json::from_str(value)
+            .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+        *self = serde_json::from_value(fields).map_err(|e| format!("{}: {}", name, e))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, serde_json::Error),
+    Serialize(PathBuf, serde_json::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            ConfigError::Parse(path, e) => write!(f, "{}: invalid config: {}", path.display(), e),
+            ConfigError::Serialize(path, e) => {
+                write!(f, "{}: can't write config: {}", path.display(), e)
+            }
+            ConfigError::Invalid(message) => write!(f, "{}", message),
         }
     }
 }
+
+impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sets_fields_by_name() {
+        let mut config = Config::default();
+        config.set_field("paddle_speed", "400").unwrap();
+        config.set_field("ai_difficulty", "hard").unwrap();
+        config.set_field("ball_sprite", "ball.png").unwrap();
+        config.set_field("ball_color", "[1, 0, 0, 1]").unwrap();
+        assert_eq!(config.paddle_speed, 400.0);
+        assert_eq!(config.ai_difficulty, AiDifficulty::Hard);
+        assert_eq!(config.ball_sprite.as_deref(), Some("ball.png"));
+        assert_eq!(config.ball_color, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(config.get_field("paddle_speed").as_deref(), Some("400.0"));
+
+        config.set_field("ball_sprite", "null").unwrap();
+        assert_eq!(config.ball_sprite, None);
+    }
+
+    #[test]
+    fn rejects_bad_fields_and_values() {
+        let mut config = Config::default();
+        assert!(config.set_field("paddle_sped", "400").is_err());
+        assert!(config.set_field("winning_score", "ten").is_err());
+        assert!(config.set_field("ai_difficulty", "impossible").is_err());
+        assert!(config.set_field("fps_target", "0").is_err());
+        assert_eq!(config.winning_score, 10);
+        assert_eq!(config.fps_target, 60);
+    }
+
+    #[test]
+    fn rejects_values_the_game_cant_use() {
+        let bad = [
+            ("ball_radius", "0"),
+            ("ball_speed", "-1"),
+            ("ball_speed_up", "0"),
+            ("ball_max_speed", "100"),
+            ("ball_max_angle", "90"),
+            ("paddle_width", "0"),
+            ("paddle_height", "601"),
+            ("paddle_speed", "-300"),
+            ("max_spin", "0"),
+            ("spin_decay", "-1"),
+            ("spin_bounce_retention", "1.5"),
+            ("screen_shake_intensity", "-2"),
+            ("winning_score", "0"),
+            ("multiball_max_balls", "0"),
+            ("max_delta_time", "0"),
+            ("record_scale", "0"),
+            ("leaderboard_size", "0"),
+            ("audio_volume", "2"),
+            ("fullscreen_size", "[0, 1080]"),
+        ];
+        let mut config = Config::default();
+        for (field, value) in bad {
+            assert!(config.set_field(field, value).is_err(), "{} = {}", field, value);
+        }
+        assert!(Config::default().validate().is_ok());
+        assert!(Config::field_names().contains(&"magnus_coefficient".to_string()));
+    }
+
+    #[test]
+    fn loads_partial_files_and_rejects_typos() {
+        let dir = std::env::temp_dir().join(format!("pong_config_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json");
+
+        std::fs::write(&path, r#"{ "paddle_speed": 450, "ai_difficulty": "easy" }"#).unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.paddle_speed, 450.0);
+        assert_eq!(config.ai_difficulty, AiDifficulty::Easy);
+        assert_eq!(config.ball_speed, Config::default().ball_speed);
+
+        std::fs::write(&path, r#"{ "paddle_sped": 450 }"#).unwrap();
+        assert!(matches!(Config::load(&path), Err(ConfigError::Parse(..))));
+        std::fs::write(&path, r#"{ "paddle_speed": 450"#).unwrap();
+        assert!(matches!(Config::load(&path), Err(ConfigError::Parse(..))));
+        std::fs::write(&path, r#"{ "window_width": 0 }"#).unwrap();
+        assert!(matches!(Config::load(&path), Err(ConfigError::Invalid(_))));
+        assert!(matches!(Config::load(dir.join("missing.json")), Err(ConfigError::Io(..))));
+
+        let saved = Config {
+            audio_volume: 0.3,
+            ..Config::default()
+        };
+        saved.save(&path).unwrap();
+        assert_eq!(Config::load(&path).unwrap().audio_volume, 0.3);
+        assert!(!dir.join("config.json.tmp").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_restart_fields() {
+        let running = Config::default();
+        let mut config = Config {
+            window_width: 1024,
+            paddle_speed: 500.0,
+            leaderboard_file: "other.json".to_string(),
+            ..Config::default()
+        };
+        assert_eq!(
+            config.keep_restart_fields(&running),
+            ["window_width", "leaderboard_file"]
+        );
+        assert_eq!(config.window_width, running.window_width);
+        assert_eq!(config.leaderboard_file, running.leaderboard_file);
+        assert_eq!(config.paddle_speed, 500.0);
+    }
+}
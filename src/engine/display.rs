@@ -0,0 +1,339 @@
+// src/engine/display.rs
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use minifb::{InputCallback, Key, KeyRepeat, MouseButton, MouseMode, Window, WindowOptions};
+
+use crate::engine::Config;
+use crate::engine::error::EngineError;
+use crate::engine::framebuffer::Framebuffer;
+use crate::{error, info, warn};
+
+/// Where finished frames go and where keyboard input comes from.
+pub trait Display {
+    fn is_open(&self) -> bool;
+    /// Whether the window has the keyboard focus.
+    fn is_focused(&mut self) -> bool;
+    fn is_key_down(&self, key: Key) -> bool;
+    /// True on the frame the key went down.
+    fn is_key_pressed(&self, key: Key) -> bool;
+    /// Like `is_key_pressed`, but also true on keyboard auto-repeat.
+    fn is_key_repeated(&self, key: Key) -> bool;
+    /// Characters typed since the last call, for text entry.
+    fn take_text(&mut self) -> String;
+    /// Current size of the drawable area in pixels.
+    fn size(&self) -> (usize, usize);
+    /// Mouse position in window pixels, if it is over the window.
+    fn mouse_position(&self) -> Option<(f32, f32)>;
+    /// True while the left mouse button is held.
+    fn is_mouse_down(&self) -> bool;
+    fn is_fullscreen(&self) -> bool;
+    fn set_fullscreen(&mut self, fullscreen: bool);
+    /// Shows a frame of exactly `size()` pixels.
+    fn present(&mut self, framebuffer: &Framebuffer) -> Result<(), EngineError>;
+}
+
+/// A real minifb window. It can be resized freely; fullscreen is a
+/// borderless window covering the primary monitor.
+pub struct WindowDisplay {
+    window: Window,
+    text: Rc<RefCell<String>>,
+    title: String,
+    windowed_size: (usize, usize),
+    windowed_position: (isize, isize),
+    fullscreen: bool,
+    fullscreen_size: Option<(usize, usize)>, // From the config, else the monitor's
+}
+
+impl WindowDisplay {
+    pub fn new(config: &Config) -> Result<Self, EngineError> {
+        let size = (config.window_width as usize, config.window_height as usize);
+        let text = Rc::new(RefCell::new(String::new()));
+        let window = Self::open(&config.window_title, size, false, &text)?;
+
+        let mut display = Self {
+            windowed_position: window.get_position(),
+            window,
+            text,
+            title: config.window_title.clone(),
+            windowed_size: size,
+            fullscreen: false,
+            fullscreen_size: config
+                .fullscreen_size
+                .map(|[width, height]| (width as usize, height as usize)),
+        };
+        if config.fullscreen {
+            display.set_fullscreen(true);
+        }
+        Ok(display)
+    }
+
+    fn open(
+        title: &str,
+        size: (usize, usize),
+        borderless: bool,
+        text: &Rc<RefCell<String>>,
+    ) -> Result<Window, minifb::Error> {
+        let options = WindowOptions {
+            borderless,
+            title: !borderless,
+            resize: !borderless,
+            topmost: borderless,
+            // Frames already match the window size, see Engine::present
+            scale_mode: minifb::ScaleMode::UpperLeft,
+            ..WindowOptions::default()
+        };
+        let mut window = Window::new(title, size.0, size.1, options)?;
+        window.limit_update_rate(Some(std::time::Duration::from_micros(16600)));
+        window.set_input_callback(Box::new(TextInput(Rc::clone(text))));
+        Ok(window)
+    }
+}
+
+/// Collects typed characters from minifb, control characters excluded.
+struct TextInput(Rc<RefCell<String>>);
+
+impl InputCallback for TextInput {
+    fn add_char(&mut self, uni_char: u32) {
+        if let Some(c) = char::from_u32(uni_char).filter(|c| !c.is_control()) {
+            self.0.borrow_mut().push(c);
+        }
+    }
+}
+
+/// Preferred mode of the first connected monitor, read from the kernel's
+/// DRM connectors. Only Linux has those; elsewhere `fullscreen_size` must
+/// be set in the config.
+fn monitor_size() -> Option<(usize, usize)> {
+    let mut connectors: Vec<_> = std::fs::read_dir("/sys/class/drm")
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    connectors.sort();
+    connectors.iter().find_map(|connector| {
+        let status = std::fs::read_to_string(connector.join("status")).ok()?;
+        if status.trim() != "connected" {
+            return None;
+        }
+        let modes = std::fs::read_to_string(connector.join("modes")).ok()?;
+        parse_mode(modes.lines().next()?)
+    })
+}
+
+/// A DRM mode line such as `1920x1080`, or `1920x1080i` when interlaced.
+fn parse_mode(mode: &str) -> Option<(usize, usize)> {
+    let (width, height) = mode.trim().split_once('x')?;
+    let height = height.trim_end_matches(|c: char| !c.is_ascii_digit());
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+impl Display for WindowDisplay {
+    fn is_open(&self) -> bool {
+        self.window.is_open()
+    }
+
+    fn is_focused(&mut self) -> bool {
+        self.window.is_active()
+    }
+
+    fn is_key_down(&self, key: Key) -> bool {
+        self.window.is_key_down(key)
+    }
+
+    fn is_key_pressed(&self, key: Key) -> bool {
+        self.window.is_key_pressed(key, KeyRepeat::No)
+    }
+
+    fn is_key_repeated(&self, key: Key) -> bool {
+        self.window.is_key_pressed(key, KeyRepeat::Yes)
+    }
+
+    fn take_text(&mut self) -> String {
+        std::mem::take(&mut *self.text.borrow_mut())
+    }
+
+    fn size(&self) -> (usize, usize) {
+        self.window.get_size()
+    }
+
+    fn mouse_position(&self) -> Option<(f32, f32)> {
+        self.window.get_mouse_pos(MouseMode::Discard)
+    }
+
+    fn is_mouse_down(&self) -> bool {
+        self.window.get_mouse_down(MouseButton::Left)
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        self.fullscreen
+    }
+
+    fn set_fullscreen(&mut self, fullscreen: bool) {
+        if fullscreen == self.fullscreen {
+            return;
+        }
+
+        let (size, position) = if fullscreen {
+            let Some(size) = self.fullscreen_size.or_else(monitor_size) else {
+                warn!("fullscreen unavailable: set fullscreen_size, the monitor size is unknown");
+                return;
+            };
+            self.windowed_size = self.window.get_size();
+            self.windowed_position = self.window.get_position();
+            (size, (0, 0))
+        } else {
+            (self.windowed_size, self.windowed_position)
+        };
+
+        // minifb can't change a window's decorations, so swap in a new one
+        match Self::open(&self.title, size, fullscreen, &self.text) {
+            Ok(mut window) => {
+                window.set_position(position.0, position.1);
+                self.window = window;
+                self.fullscreen = fullscreen;
+                info!("display mode changed"; fullscreen = fullscreen, width = size.0, height = size.1);
+            }
+            Err(e) => error!("failed to switch fullscreen"; error = e),
+        }
+    }
+
+    fn present(&mut self, framebuffer: &Framebuffer) -> Result<(), EngineError> {
+        self.window.update_with_buffer(
+            framebuffer.pixels(),
+            framebuffer.width(),
+            framebuffer.height(),
+        )?;
+        Ok(())
+    }
+}
+
+/// No window at all, for tests and offline rendering. Frames are counted
+/// and dropped; keys, text, the mouse and the window size can be set from
+/// code.
+pub struct HeadlessDisplay {
+    keys_down: HashSet<Key>,
+    keys_pressed: HashSet<Key>, // Until the next present
+    text: String,
+    size: (usize, usize),
+    mouse_position: Option<(f32, f32)>,
+    mouse_down: bool,
+    focused: bool,
+    fullscreen: bool,
+    pub frames_presented: u64,
+}
+
+#[allow(dead_code)]
+impl HeadlessDisplay {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            keys_down: HashSet::new(),
+            keys_pressed: HashSet::new(),
+            text: String::new(),
+            size: (width, height),
+            mouse_position: None,
+            mouse_down: false,
+            focused: true,
+            fullscreen: false,
+            frames_presented: 0,
+        }
+    }
+
+    pub fn set_key_down(&mut self, key: Key, down: bool) {
+        if down {
+            self.keys_down.insert(key);
+        } else {
+            self.keys_down.remove(&key);
+        }
+    }
+
+    /// Presses `key` for the current frame.
+    pub fn press_key(&mut self, key: Key) {
+        self.keys_pressed.insert(key);
+    }
+
+    pub fn type_text(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.size = (width, height);
+    }
+
+    pub fn set_mouse_position(&mut self, position: Option<(f32, f32)>) {
+        self.mouse_position = position;
+    }
+
+    pub fn set_mouse_down(&mut self, down: bool) {
+        self.mouse_down = down;
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+}
+
+impl Display for HeadlessDisplay {
+    fn is_open(&self) -> bool {
+        true
+    }
+
+    fn is_focused(&mut self) -> bool {
+        self.focused
+    }
+
+    fn is_key_down(&self, key: Key) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    fn is_key_pressed(&self, key: Key) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    fn is_key_repeated(&self, key: Key) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    fn take_text(&mut self) -> String {
+        std::mem::take(&mut self.text)
+    }
+
+    fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    fn mouse_position(&self) -> Option<(f32, f32)> {
+        self.mouse_position
+    }
+
+    fn is_mouse_down(&self) -> bool {
+        self.mouse_down
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        self.fullscreen
+    }
+
+    fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.fullscreen = fullscreen;
+    }
+
+    fn present(&mut self, framebuffer: &Framebuffer) -> Result<(), EngineError> {
+        debug_assert_eq!((framebuffer.width(), framebuffer.height()), self.size);
+        self.frames_presented += 1;
+        self.keys_pressed.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_drm_modes() {
+        assert_eq!(parse_mode("1920x1080\n"), Some((1920, 1080)));
+        assert_eq!(parse_mode("1920x1080i"), Some((1920, 1080)));
+        assert_eq!(parse_mode("preferred"), None);
+    }
+}
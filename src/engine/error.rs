@@ -0,0 +1,55 @@
+// src/engine/error.rs
+use std::fmt;
+
+use crate::assets::AssetError;
+use crate::engine::config::ConfigError;
+
+/// Everything that can stop the engine from starting or running.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum EngineError {
+    Window(minifb::Error),
+    Config(ConfigError),
+    Asset(AssetError),
+    Audio(String),
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::Window(e) => write!(f, "window error: {}", e),
+            EngineError::Config(e) => write!(f, "config error: {}", e),
+            EngineError::Asset(e) => write!(f, "asset error: {}", e),
+            EngineError::Audio(message) => write!(f, "audio error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EngineError::Window(e) => Some(e),
+            EngineError::Config(e) => Some(e),
+            EngineError::Asset(e) => Some(e),
+            EngineError::Audio(_) => None,
+        }
+    }
+}
+
+impl From<minifb::Error> for EngineError {
+    fn from(e: minifb::Error) -> Self {
+        EngineError::Window(e)
+    }
+}
+
+impl From<ConfigError> for EngineError {
+    fn from(e: ConfigError) -> Self {
+        EngineError::Config(e)
+    }
+}
+
+impl From<AssetError> for EngineError {
+    fn from(e: AssetError) -> Self {
+        EngineError::Asset(e)
+    }
+}
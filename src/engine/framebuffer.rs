@@ -0,0 +1,754 @@
+// src/engine/framebuffer.rs
+//
+// Software rasterizer. Primitives take colours as 0xAARRGGBB: alpha 0xFF
+// overwrites the pixel, anything lower is blended over it. Pixels are stored
+// as 0x00RRGGBB, the format minifb presents. Everything clips against the
+// framebuffer, so shapes may be partly or fully off screen.
+
+use std::path::Path;
+
+use crate::assets::{AssetError, save_png};
+
+/// Makes an opaque colour out of 0xRRGGBB.
+pub const fn opaque(rgb: u32) -> u32 {
+    0xFF00_0000 | (rgb & 0x00FF_FFFF)
+}
+
+/// Makes a colour out of 0xRRGGBB and an alpha between 0.0 and 1.0.
+pub fn with_alpha(rgb: u32, alpha: f32) -> u32 {
+    let alpha = (alpha.clamp(0.0, 1.0) * 255.0).round() as u32;
+    (alpha << 24) | (rgb & 0x00FF_FFFF)
+}
+
+/// Makes a 0xAARRGGBB colour out of a config's `[r, g, b, a]`, each 0.0
+/// to 1.0.
+pub fn from_rgba(rgba: [f32; 4]) -> u32 {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u32;
+    with_alpha(
+        (channel(rgba[0]) << 16) | (channel(rgba[1]) << 8) | channel(rgba[2]),
+        rgba[3],
+    )
+}
+
+/// Linear interpolation between two 0xRRGGBB colours.
+pub fn lerp_color(from: u32, to: u32, amount: f32) -> u32 {
+    let amount = amount.clamp(0.0, 1.0);
+    let channel = |shift: u32| {
+        let a = ((from >> shift) & 0xFF) as f32;
+        let b = ((to >> shift) & 0xFF) as f32;
+        ((a + (b - a) * amount).round() as u32) << shift
+    };
+    channel(16) | channel(8) | channel(0)
+}
+
+// Fractional part that stays positive for negative values, unlike `fract`.
+fn fpart(value: f32) -> f32 {
+    value - value.floor()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<u32>,
+}
+
+#[allow(dead_code)]
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Rows of 0x00RRGGBB pixels, top to bottom.
+    pub fn pixels(&self) -> &[u32] {
+        &self.pixels
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [u32] {
+        &mut self.pixels
+    }
+
+    pub fn clear(&mut self, rgb: u32) {
+        self.pixels.fill(rgb & 0x00FF_FFFF);
+    }
+
+    /// The frame as opaque RGBA bytes, ready for an image encoder.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|&p| [(p >> 16) as u8, (p >> 8) as u8, p as u8, 0xFF])
+            .collect()
+    }
+
+    pub fn save_png(&self, path: &Path) -> Result<(), AssetError> {
+        save_png(path, self.width, self.height, &self.to_rgba())
+    }
+
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<u32> {
+        self.index(x, y).map(|index| self.pixels[index])
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            None
+        } else {
+            Some(y as usize * self.width + x as usize)
+        }
+    }
+
+    pub fn set_pixel(&mut self, x: i32, y: i32, color: u32) {
+        self.blend_pixel(x, y, color, 1.0);
+    }
+
+    /// Draws `color` with its alpha further scaled by `coverage`, used for
+    /// antialiased edges.
+    pub fn blend_pixel(&mut self, x: i32, y: i32, color: u32, coverage: f32) {
+        let Some(index) = self.index(x, y) else {
+            return;
+        };
+        let alpha = ((color >> 24) as f32 * coverage.clamp(0.0, 1.0)).round() as u32;
+        if alpha == 0 {
+            return;
+        }
+        if alpha >= 255 {
+            self.pixels[index] = color & 0x00FF_FFFF;
+            return;
+        }
+
+        let dst = self.pixels[index];
+        let channel = |shift: u32| {
+            let s = (color >> shift) & 0xFF;
+            let d = (dst >> shift) & 0xFF;
+            ((s * alpha + d * (255 - alpha) + 127) / 255) << shift
+        };
+        self.pixels[index] = channel(16) | channel(8) | channel(0);
+    }
+
+    pub fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: u32) {
+        let x0 = x.max(0);
+        let y0 = y.max(0);
+        let x1 = x.saturating_add(width).min(self.width as i32);
+        let y1 = y.saturating_add(height).min(self.height as i32);
+        for py in y0..y1 {
+            for px in x0..x1 {
+                self.set_pixel(px, py, color);
+            }
+        }
+    }
+
+    /// One pixel wide rectangle outline.
+    pub fn rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: u32) {
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        self.fill_rect(x, y, width, 1, color);
+        if height > 1 {
+            self.fill_rect(x, y + height - 1, width, 1, color);
+        }
+        self.fill_rect(x, y + 1, 1, height - 2, color);
+        if width > 1 {
+            self.fill_rect(x + width - 1, y + 1, 1, height - 2, color);
+        }
+    }
+
+    /// Bresenham line, both end points included.
+    pub fn line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: u32) {
+        if let Some((x0, y0, x1, y1, _)) = self.clip_line(x0, y0, x1, y1) {
+            self.bresenham(x0, y0, x1, y1, true, color);
+        }
+    }
+
+    /// Liang–Barsky: the part of the line inside the framebuffer, so drawing
+    /// never steps through off-screen pixels. The flag is set when the end
+    /// point was moved.
+    fn clip_line(
+        &self,
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+    ) -> Option<(i32, i32, i32, i32, bool)> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+        let (x0, y0) = (x0 as f64, y0 as f64);
+        let (dx, dy) = (x1 as f64 - x0, y1 as f64 - y0);
+        let (max_x, max_y) = ((self.width - 1) as f64, (self.height - 1) as f64);
+        let (mut t0, mut t1) = (0.0, 1.0);
+        for (p, q) in [(-dx, x0), (dx, max_x - x0), (-dy, y0), (dy, max_y - y0)] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+                continue;
+            }
+            let t = q / p;
+            if p < 0.0 {
+                if t > t1 {
+                    return None;
+                }
+                t0 = f64::max(t0, t);
+            } else {
+                if t < t0 {
+                    return None;
+                }
+                t1 = f64::min(t1, t);
+            }
+        }
+        let point = |t: f64| ((x0 + t * dx).round() as i32, (y0 + t * dy).round() as i32);
+        let (start, end) = (point(t0), point(t1));
+        Some((start.0, start.1, end.0, end.1, t1 < 1.0))
+    }
+
+    fn bresenham(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, with_end: bool, color: u32) {
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let step_x = if x0 < x1 { 1 } else { -1 };
+        let step_y = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        let (mut x, mut y) = (x0, y0);
+
+        loop {
+            if x == x1 && y == y1 {
+                if with_end {
+                    self.set_pixel(x, y, color);
+                }
+                break;
+            }
+            self.set_pixel(x, y, color);
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += step_x;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    /// Xiaolin Wu antialiased line between pixel coordinates.
+    pub fn line_aa(&mut self, x0: f32, y0: f32, x1: f32, y1: f32, color: u32) {
+        let steep = (y1 - y0).abs() > (x1 - x0).abs();
+        let (mut x0, mut y0, mut x1, mut y1) = if steep {
+            (y0, x0, y1, x1)
+        } else {
+            (x0, y0, x1, y1)
+        };
+        if x0 > x1 {
+            std::mem::swap(&mut x0, &mut x1);
+            std::mem::swap(&mut y0, &mut y1);
+        }
+
+        let dx = x1 - x0;
+        let gradient = if dx == 0.0 { 1.0 } else { (y1 - y0) / dx };
+        let plot = |fb: &mut Self, x: i32, y: i32, coverage: f32| {
+            if steep {
+                fb.blend_pixel(y, x, color, coverage);
+            } else {
+                fb.blend_pixel(x, y, color, coverage);
+            }
+        };
+
+        // First end point
+        let x_end = x0.round();
+        let y_end = y0 + gradient * (x_end - x0);
+        let x_gap = 1.0 - fpart(x0 + 0.5);
+        let start_x = x_end as i32;
+        let start_y = y_end.floor() as i32;
+        plot(self, start_x, start_y, (1.0 - fpart(y_end)) * x_gap);
+        plot(self, start_x, start_y.saturating_add(1), fpart(y_end) * x_gap);
+        let first_y = y_end;
+
+        // Second end point
+        let x_end = x1.round();
+        let y_end = y1 + gradient * (x_end - x1);
+        let x_gap = fpart(x1 + 0.5);
+        let end_x = x_end as i32;
+        let end_y = y_end.floor() as i32;
+        plot(self, end_x, end_y, (1.0 - fpart(y_end)) * x_gap);
+        plot(self, end_x, end_y.saturating_add(1), fpart(y_end) * x_gap);
+
+        // Only step across the columns (rows when steep) that are on screen
+        let columns = if steep { self.height } else { self.width } as i32;
+        let first = start_x.saturating_add(1).max(0);
+        let last = end_x.min(columns);
+        let mut inter_y = first_y + gradient * (first as f32 - start_x as f32);
+        for x in first..last {
+            let y = inter_y.floor() as i32;
+            plot(self, x, y, 1.0 - fpart(inter_y));
+            plot(self, x, y.saturating_add(1), fpart(inter_y));
+            inter_y += gradient;
+        }
+    }
+
+    /// Midpoint circle outline.
+    pub fn circle(&mut self, cx: i32, cy: i32, radius: i32, color: u32) {
+        if radius < 0 {
+            return;
+        }
+        let (mut x, mut y) = (radius, 0);
+        let mut err = 1 - radius;
+        while x >= y {
+            // Each octant point once, so translucent outlines don't double up
+            let mut points = [
+                (x, y),
+                (y, x),
+                (-y, x),
+                (-x, y),
+                (-x, -y),
+                (-y, -x),
+                (y, -x),
+                (x, -y),
+            ];
+            points.sort_unstable();
+            let mut last = None;
+            for point in points {
+                if last != Some(point) {
+                    self.set_pixel(cx + point.0, cy + point.1, color);
+                    last = Some(point);
+                }
+            }
+
+            y += 1;
+            if err < 0 {
+                err += 2 * y + 1;
+            } else {
+                x -= 1;
+                err += 2 * (y - x) + 1;
+            }
+        }
+    }
+
+    /// Filled circle on the pixel grid. Uses `r² + r` as the bound so the
+    /// disc lines up with the `circle` outline of the same radius.
+    pub fn fill_circle(&mut self, cx: i32, cy: i32, radius: i32, color: u32) {
+        if radius < 0 {
+            return;
+        }
+        // In i64 so huge radii can't overflow, and only over on-screen rows
+        let (cx, cy, radius) = (cx as i64, cy as i64, radius as i64);
+        let limit = radius * radius + radius;
+        let (width, height) = (self.width as i64, self.height as i64);
+        for py in (cy - radius).max(0)..=(cy + radius).min(height - 1) {
+            for px in (cx - radius).max(0)..=(cx + radius).min(width - 1) {
+                let (dx, dy) = (px - cx, py - cy);
+                if dx * dx + dy * dy <= limit {
+                    self.set_pixel(px as i32, py as i32, color);
+                }
+            }
+        }
+    }
+
+    /// Filled circle at a sub-pixel position, edge pixels weighted by how
+    /// much of them the disc covers.
+    pub fn fill_circle_aa(&mut self, cx: f32, cy: f32, radius: f32, color: u32) {
+        let min_x = (cx - radius - 1.0).floor() as i32;
+        let max_x = (cx + radius + 1.0).ceil() as i32;
+        let min_y = (cy - radius - 1.0).floor() as i32;
+        let max_y = (cy + radius + 1.0).ceil() as i32;
+
+        for py in min_y.max(0)..=max_y.min(self.height as i32 - 1) {
+            for px in min_x.max(0)..=max_x.min(self.width as i32 - 1) {
+                // Distance from the pixel centre to the circle centre
+                let dx = px as f32 + 0.5 - cx;
+                let dy = py as f32 + 0.5 - cy;
+                let distance = (dx * dx + dy * dy).sqrt();
+                let coverage = (radius + 0.5 - distance).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    self.blend_pixel(px, py, color, coverage);
+                }
+            }
+        }
+    }
+
+    /// Closed polygon outline through `points`.
+    pub fn polygon(&mut self, points: &[(f32, f32)], color: u32) {
+        for (i, &(x0, y0)) in points.iter().enumerate() {
+            let (x1, y1) = points[(i + 1) % points.len()];
+            let (x1, y1) = (x1.round() as i32, y1.round() as i32);
+            if points.len() > 1 {
+                // Skip the shared end point so translucent corners are drawn once
+                self.line_without_end(x0.round() as i32, y0.round() as i32, x1, y1, color);
+            }
+        }
+        if points.len() == 1 {
+            self.set_pixel(points[0].0.round() as i32, points[0].1.round() as i32, color);
+        }
+    }
+
+    fn line_without_end(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: u32) {
+        if x0 == x1 && y0 == y1 {
+            return;
+        }
+        // An end point off screen isn't drawn anyway, the clipped one is
+        if let Some((x0, y0, x1, y1, end_clipped)) = self.clip_line(x0, y0, x1, y1) {
+            self.bresenham(x0, y0, x1, y1, end_clipped, color);
+        }
+    }
+
+    /// Scanline fill with the even-odd rule. A pixel is filled when its
+    /// centre is inside, so a polygon from (0, 0) to (4, 3) covers exactly
+    /// the same pixels as `fill_rect(0, 0, 4, 3)`.
+    pub fn fill_polygon(&mut self, points: &[(f32, f32)], color: u32) {
+        if points.len() < 3 {
+            return;
+        }
+        let min_y = points.iter().map(|p| p.1).fold(f32::INFINITY, f32::min);
+        let max_y = points.iter().map(|p| p.1).fold(f32::NEG_INFINITY, f32::max);
+        let first_row = (min_y.floor() as i32).max(0);
+        let last_row = (max_y.ceil() as i32).min(self.height as i32 - 1);
+
+        let mut crossings = Vec::new();
+        for row in first_row..=last_row {
+            let scan_y = row as f32 + 0.5;
+            crossings.clear();
+            for (i, &(x0, y0)) in points.iter().enumerate() {
+                let (x1, y1) = points[(i + 1) % points.len()];
+                // Half-open so a vertex on the scanline is counted once
+                if (y0 <= scan_y && y1 > scan_y) || (y1 <= scan_y && y0 > scan_y) {
+                    crossings.push(x0 + (scan_y - y0) / (y1 - y0) * (x1 - x0));
+                }
+            }
+            crossings.sort_by(f32::total_cmp);
+
+            for span in crossings.chunks_exact(2) {
+                // Pixels whose centre lies in [start, end)
+                let start = (span[0] - 0.5).ceil() as i32;
+                let end = (span[1] - 0.5).ceil() as i32;
+                for x in start.max(0)..end.min(self.width as i32) {
+                    self.set_pixel(x, row, color);
+                }
+            }
+        }
+    }
+
+    /// Filled rectangle with quarter-circle corners of `radius`.
+    pub fn fill_rounded_rect(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        radius: i32,
+        color: u32,
+    ) {
+        let radius = radius.clamp(0, width.min(height) / 2);
+        let r = radius as f32;
+        let (right, bottom) = (x.saturating_add(width), y.saturating_add(height));
+        let (left_arc, top_arc) = (x.saturating_add(radius), y.saturating_add(radius));
+        let (right_arc, bottom_arc) = (right - radius, bottom - radius);
+        for py in y.max(0)..bottom.min(self.height as i32) {
+            for px in x.max(0)..right.min(self.width as i32) {
+                // Nearest corner circle centre, if the pixel is in a corner
+                let corner_x = if px < left_arc {
+                    Some(left_arc as f32)
+                } else if px >= right_arc {
+                    Some(right_arc as f32)
+                } else {
+                    None
+                };
+                let corner_y = if py < top_arc {
+                    Some(top_arc as f32)
+                } else if py >= bottom_arc {
+                    Some(bottom_arc as f32)
+                } else {
+                    None
+                };
+
+                if let (Some(cx), Some(cy)) = (corner_x, corner_y) {
+                    let dx = px as f32 + 0.5 - cx;
+                    let dy = py as f32 + 0.5 - cy;
+                    if dx * dx + dy * dy > r * r {
+                        continue;
+                    }
+                }
+                self.set_pixel(px, py, color);
+            }
+        }
+    }
+
+    /// Draws an image of 0xAARRGGBB pixels with its top-left corner at
+    /// `(x, y)`, blending each pixel by its alpha. Draws nothing if
+    /// `pixels` isn't exactly `width` x `height`.
+    pub fn blit(&mut self, x: i32, y: i32, width: usize, height: usize, pixels: &[u32]) {
+        if pixels.len() != width * height {
+            return;
+        }
+        for row in 0..height {
+            for col in 0..width {
+                let color = pixels[row * width + col];
+                self.set_pixel(x + col as i32, y + row as i32, color);
+            }
+        }
+    }
+
+    /// Like `blit`, but stretches the image to `width` x `height` pixels,
+    /// sampling the nearest source pixel.
+    #[allow(clippy::too_many_arguments)]
+    pub fn blit_scaled(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: usize,
+        height: usize,
+        src_width: usize,
+        src_height: usize,
+        pixels: &[u32],
+    ) {
+        if src_width == 0 || src_height == 0 || pixels.len() != src_width * src_height {
+            return;
+        }
+        for row in 0..height {
+            let src_row = row * src_height / height;
+            for col in 0..width {
+                let src_col = col * src_width / width;
+                let color = pixels[src_row * src_width + src_col];
+                self.set_pixel(x + col as i32, y + row as i32, color);
+            }
+        }
+    }
+
+    /// Moves the whole image by `(dx, dy)`, filling the exposed edges.
+    pub fn shift(&mut self, dx: i32, dy: i32, fill: u32) {
+        if dx == 0 && dy == 0 {
+            return;
+        }
+        let source = self.pixels.clone();
+        let (width, height) = (self.width as i32, self.height as i32);
+        for y in 0..height {
+            for x in 0..width {
+                let (sx, sy) = (x - dx, y - dy);
+                self.pixels[(y * width + x) as usize] =
+                    if sx >= 0 && sx < width && sy >= 0 && sy < height {
+                        source[(sy * width + sx) as usize]
+                    } else {
+                        fill & 0x00FF_FFFF
+                    };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: u32 = opaque(0xFFFFFF);
+
+    /// '#' for lit pixels, '.' for black ones.
+    fn ascii(fb: &Framebuffer) -> Vec<String> {
+        fb.pixels()
+            .chunks(fb.width())
+            .map(|row| row.iter().map(|&p| if p != 0 { '#' } else { '.' }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn fill_rect_clips_to_the_framebuffer() {
+        let mut fb = Framebuffer::new(4, 3);
+        fb.fill_rect(-2, 1, 4, 5, WHITE);
+        assert_eq!(ascii(&fb), ["....", "##..", "##.."]);
+    }
+
+    #[test]
+    fn rect_outline() {
+        let mut fb = Framebuffer::new(5, 4);
+        fb.rect(0, 0, 5, 4, WHITE);
+        assert_eq!(ascii(&fb), ["#####", "#...#", "#...#", "#####"]);
+    }
+
+    #[test]
+    fn bresenham_lines() {
+        let mut fb = Framebuffer::new(5, 3);
+        fb.line(0, 0, 4, 2, WHITE);
+        assert_eq!(ascii(&fb), ["#....", ".##..", "...##"]);
+
+        let mut fb = Framebuffer::new(3, 4);
+        fb.line(2, 3, 0, 0, WHITE);
+        assert_eq!(ascii(&fb), ["#..", ".#.", ".#.", "..#"]);
+    }
+
+    #[test]
+    fn line_clips_off_screen_parts() {
+        let mut fb = Framebuffer::new(3, 3);
+        fb.line(-5, 1, 10, 1, WHITE);
+        assert_eq!(ascii(&fb), ["...", "###", "..."]);
+
+        // Far off ends are clipped before stepping, not walked to
+        let mut fb = Framebuffer::new(3, 3);
+        fb.line(i32::MIN, i32::MIN, i32::MAX, i32::MAX, WHITE);
+        assert_eq!(ascii(&fb), ["#..", ".#.", "..#"]);
+        let mut fb = Framebuffer::new(3, 3);
+        fb.polygon(&[(1.0, 0.0), (1.0, 1e9)], WHITE);
+        assert_eq!(ascii(&fb), [".#.", ".#.", ".#."]);
+        fb.line(-10, -10, -1, 5, WHITE);
+        assert_eq!(ascii(&fb), [".#.", ".#.", ".#."]);
+    }
+
+    #[test]
+    fn antialiased_horizontal_line_is_solid() {
+        let mut fb = Framebuffer::new(5, 3);
+        fb.line_aa(0.0, 1.0, 4.0, 1.0, WHITE);
+        assert_eq!(ascii(&fb), [".....", "#####", "....."]);
+        assert_eq!(fb.get_pixel(2, 1), Some(0xFFFFFF));
+    }
+
+    #[test]
+    fn antialiased_line_only_steps_on_screen() {
+        let mut fb = Framebuffer::new(5, 3);
+        fb.line_aa(-1e9, 1.0, 1e9, 1.0, WHITE);
+        assert_eq!(ascii(&fb), [".....", "#####", "....."]);
+
+        // Coordinates past i32 saturate instead of overflowing
+        let mut fb = Framebuffer::new(5, 3);
+        fb.line_aa(0.0, 1e10, 4.0, 1e10, WHITE);
+        fb.line_aa(1e10, 0.0, 1e10, 2.0, WHITE);
+        assert_eq!(ascii(&fb), [".....", ".....", "....."]);
+    }
+
+    #[test]
+    fn antialiased_line_splits_coverage_between_rows() {
+        let mut fb = Framebuffer::new(5, 3);
+        fb.line_aa(0.0, 0.5, 4.0, 0.5, WHITE);
+        assert_eq!(fb.get_pixel(2, 0), Some(0x808080));
+        assert_eq!(fb.get_pixel(2, 1), Some(0x808080));
+        assert_eq!(fb.get_pixel(2, 2), Some(0));
+    }
+
+    #[test]
+    fn circle_outline() {
+        let mut fb = Framebuffer::new(5, 5);
+        fb.circle(2, 2, 2, WHITE);
+        assert_eq!(ascii(&fb), [".###.", "#...#", "#...#", "#...#", ".###."]);
+    }
+
+    #[test]
+    fn filled_circle() {
+        let mut fb = Framebuffer::new(5, 5);
+        fb.fill_circle(2, 2, 2, WHITE);
+        assert_eq!(ascii(&fb), [".###.", "#####", "#####", "#####", ".###."]);
+
+        // Huge radii neither overflow nor walk off-screen rows
+        let mut fb = Framebuffer::new(3, 3);
+        fb.fill_circle(1, 1, 100_000, WHITE);
+        assert_eq!(ascii(&fb), ["###", "###", "###"]);
+        let mut fb = Framebuffer::new(3, 3);
+        fb.fill_circle(i32::MIN, 1, i32::MAX, WHITE);
+        assert_eq!(ascii(&fb), ["...", "...", "..."]);
+    }
+
+    #[test]
+    fn antialiased_circle_has_solid_centre_and_soft_edge() {
+        let mut fb = Framebuffer::new(9, 9);
+        fb.fill_circle_aa(4.5, 4.5, 3.0, WHITE);
+        assert_eq!(fb.get_pixel(4, 4), Some(0xFFFFFF));
+        assert_eq!(fb.get_pixel(0, 0), Some(0));
+        let edge = fb.get_pixel(4, 1).unwrap() & 0xFF;
+        assert!(edge > 0 && edge < 0xFF, "edge pixel {:#x}", edge);
+    }
+
+    #[test]
+    fn filled_polygon_matches_rect() {
+        let mut polygon = Framebuffer::new(6, 5);
+        polygon.fill_polygon(&[(1.0, 1.0), (5.0, 1.0), (5.0, 4.0), (1.0, 4.0)], WHITE);
+        let mut rect = Framebuffer::new(6, 5);
+        rect.fill_rect(1, 1, 4, 3, WHITE);
+        assert_eq!(polygon, rect);
+    }
+
+    #[test]
+    fn filled_triangle() {
+        let mut fb = Framebuffer::new(5, 3);
+        fb.fill_polygon(&[(0.0, 0.0), (5.0, 0.0), (0.0, 3.0)], WHITE);
+        assert_eq!(ascii(&fb), ["####.", "##...", "#...."]);
+    }
+
+    #[test]
+    fn polygon_outline() {
+        let mut fb = Framebuffer::new(4, 4);
+        fb.polygon(&[(0.0, 0.0), (3.0, 0.0), (3.0, 3.0), (0.0, 3.0)], WHITE);
+        assert_eq!(ascii(&fb), ["####", "#..#", "#..#", "####"]);
+    }
+
+    #[test]
+    fn rounded_rect_cuts_corners() {
+        let mut fb = Framebuffer::new(6, 6);
+        fb.fill_rounded_rect(0, 0, 6, 6, 2, WHITE);
+        assert_eq!(
+            ascii(&fb),
+            [".####.", "######", "######", "######", "######", ".####."]
+        );
+
+        let mut fb = Framebuffer::new(2, 2);
+        fb.fill_rounded_rect(0, 1, i32::MAX, i32::MAX, 0, WHITE);
+        assert_eq!(ascii(&fb), ["..", "##"]);
+    }
+
+    #[test]
+    fn alpha_blends_over_existing_pixels() {
+        let mut fb = Framebuffer::new(1, 1);
+        fb.clear(0x0000FF);
+        fb.set_pixel(0, 0, 0x80FF_0000);
+        assert_eq!(fb.get_pixel(0, 0), Some(0x80007F));
+    }
+
+    #[test]
+    fn translucent_shapes_draw_each_pixel_once() {
+        let mut fb = Framebuffer::new(5, 5);
+        fb.circle(2, 2, 2, with_alpha(0xFFFFFF, 0.5));
+        assert_eq!(fb.get_pixel(0, 2), Some(0x808080));
+        assert_eq!(fb.get_pixel(2, 0), Some(0x808080));
+    }
+
+    #[test]
+    fn blit_respects_source_alpha_and_clipping() {
+        let mut fb = Framebuffer::new(3, 2);
+        let image = [WHITE, 0x0000_0000, 0x80FF_FFFF, WHITE];
+        fb.blit(1, 0, 2, 2, &image);
+        assert_eq!(fb.pixels(), &[0, 0xFFFFFF, 0, 0, 0x808080, 0xFFFFFF]);
+
+        let mut fb = Framebuffer::new(2, 2);
+        fb.blit(-1, -1, 2, 2, &image);
+        assert_eq!(fb.pixels(), &[0xFFFFFF, 0, 0, 0]);
+
+        // A short image is skipped rather than read past its end
+        let mut fb = Framebuffer::new(2, 2);
+        fb.blit(0, 0, 2, 2, &image[..3]);
+        fb.blit_scaled(0, 0, 2, 2, 2, 2, &image[..3]);
+        assert_eq!(fb.pixels(), &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn blit_scaled_repeats_nearest_pixels() {
+        let mut fb = Framebuffer::new(4, 2);
+        fb.blit_scaled(0, 0, 4, 2, 2, 1, &[WHITE, opaque(0x0000FF)]);
+        assert_eq!(
+            fb.pixels(),
+            &[
+                0xFFFFFF, 0xFFFFFF, 0x0000FF, 0x0000FF, 0xFFFFFF, 0xFFFFFF, 0x0000FF, 0x0000FF
+            ]
+        );
+    }
+
+    #[test]
+    fn shift_moves_image_and_fills_edges() {
+        let mut fb = Framebuffer::new(3, 1);
+        fb.pixels_mut().copy_from_slice(&[1, 2, 3]);
+        fb.shift(1, 0, 9);
+        assert_eq!(fb.pixels(), &[9, 1, 2]);
+    }
+}
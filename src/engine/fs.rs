@@ -0,0 +1,55 @@
+// src/engine/fs.rs
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Writes `bytes` to `path` through a temporary file that is synced and
+/// renamed over it, so a crash leaves either the old file or the new one.
+pub fn atomic_write(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    replace(path, bytes, None)
+}
+
+/// Like [`atomic_write`], but moves the previous file, if any, to `backup`
+/// just before the new one takes its place.
+pub fn atomic_write_with_backup(path: &Path, bytes: &[u8], backup: &Path) -> io::Result<()> {
+    replace(path, bytes, Some(backup))
+}
+
+/// `path` with `.<suffix>` appended, e.g. `scores.json.tmp`.
+pub fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", suffix));
+    name.into()
+}
+
+fn replace(path: &Path, bytes: &[u8], backup: Option<&Path>) -> io::Result<()> {
+    let temporary = with_suffix(path, "tmp");
+    let mut file = std::fs::File::create(&temporary)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+
+    if let Some(backup) = backup.filter(|_| path.exists()) {
+        std::fs::rename(path, backup)?;
+    }
+    std::fs::rename(&temporary, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_files_and_keeps_a_backup() {
+        let dir = std::env::temp_dir().join(format!("pong_fs_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("data.json");
+        let backup = with_suffix(&path, "bak");
+
+        atomic_write(&path, b"one").unwrap();
+        atomic_write_with_backup(&path, b"two", &backup).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"two");
+        assert_eq!(std::fs::read(&backup).unwrap(), b"one");
+        assert!(!with_suffix(&path, "tmp").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,88 +1,394 @@
 // src/engine/game.rs
-use crate::components::{Ball, Paddle, Score};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::assets::{AssetError, Assets, Sprite, SpriteSheet};
+use crate::components::{Ball, DebugInfo, GameMode, Paddle, Particles, Score, ScreenShake, Trail};
+use crate::ecs::ComponentStorage;
 use crate::engine::Config;
+use crate::engine::config::ConfigError;
+use crate::engine::date::civil_from_days;
+use crate::engine::display::{Display, HeadlessDisplay, WindowDisplay};
+use crate::engine::error::EngineError;
+use crate::engine::framebuffer::{Framebuffer, from_rgba, lerp_color, opaque, with_alpha};
+use crate::engine::overlay::DebugOverlay;
+use crate::engine::profiler::Profiler;
+use crate::engine::recorder::Recorder;
 use crate::engine::time::Timer;
-use minifb::{Key, Window, WindowOptions};
+use crate::engine::viewport::Viewport;
+use crate::{debug, error, info, trace, warn};
+use minifb::Key;
 
 pub struct Engine {
-    window: Window,
-    buffer: Vec<u32>,
+    display: Box<dyn Display>,
+    buffer: Framebuffer, // The frame at the logical resolution
+    screen: Framebuffer, // `buffer` scaled to the window, when they differ
     timer: Timer,
     pub config: Config,
     running: bool,
+    paddle_sprite: Option<Sprite>,
+    ball_sprite: Option<SpriteSheet>,
+    recorder: Option<Recorder>,
+    recording_error: Option<AssetError>, // Why the last recording stopped early
+    input_captured: bool, // A text field or menu has the keyboard
+    mouse_down: bool,     // Left button state at the last present
+    mouse_clicked: bool,  // The left button went down before the last present
 }
 
 #[allow(dead_code)]
 impl Engine {
-    pub fn new(config: Config) -> Self {
-        let mut window = Window::new(
-            &config.window_title,
-            config.window_width as usize,
-            config.window_height as usize,
-            WindowOptions::default(),
-        )
-        .unwrap_or_else(|e| {
-            panic!("Failed to create window: {}", e);
-        });
+    pub fn new(config: Config) -> Result<Self, EngineError> {
+        let display = WindowDisplay::new(&config)?;
+        Self::with_display(config, Box::new(display))
+    }
 
-        window.limit_update_rate(Some(std::time::Duration::from_micros(16600)));
+    /// An engine without a window, for tests and offline rendering.
+    pub fn headless(config: Config) -> Result<Self, EngineError> {
+        let display = HeadlessDisplay::new(config.window_width as usize, config.window_height as usize);
+        Self::with_display(config, Box::new(display))
+    }
 
+    /// Fails if the config has values the engine can't run with.
+    pub fn with_display(config: Config, display: Box<dyn Display>) -> Result<Self, EngineError> {
+        config.validate().map_err(ConfigError::Invalid)?;
         // Initialize the game components
-        let buffer = vec![0; (config.window_width * config.window_height) as usize];
-        let timer = Timer::new(config.fps_target);
+        let buffer = Framebuffer::new(config.window_width as usize, config.window_height as usize);
+        let mut timer = Timer::new(config.fps_target);
+        timer.set_max_delta(config.max_delta_time);
 
-        Self {
-            window,
+        let mut engine = Self {
+            display,
             buffer,
+            screen: Framebuffer::new(0, 0),
             timer,
             config,
             running: true,
+            paddle_sprite: None,
+            ball_sprite: None,
+            recorder: None,
+            recording_error: None,
+            input_captured: false,
+            mouse_down: false,
+            mouse_clicked: false,
+        };
+        engine.load_sprites();
+        Ok(engine)
+    }
+
+    /// Loads the sprites named in the config. Anything that fails to load is
+    /// reported and drawn as plain shapes instead.
+    pub fn load_sprites(&mut self) {
+        let mut assets = Assets::new(&self.config.asset_dir);
+        if let Some(atlas) = &self.config.sprite_atlas
+            && let Err(e) = assets.load_atlas(atlas)
+        {
+            warn!("failed to load sprite atlas"; error = e);
         }
+
+        let mut load = |name: &Option<String>| {
+            let name = name.as_ref()?;
+            match assets.sprite(name) {
+                Ok(sprite) => Some(sprite),
+                Err(e) => {
+                    warn!("failed to load sprite"; error = e);
+                    None
+                }
+            }
+        };
+
+        self.paddle_sprite = load(&self.config.paddle_sprite);
+        self.ball_sprite = load(&self.config.ball_sprite).map(|sprite| {
+            let frames = self.config.ball_sprite_frames.max(1);
+            SpriteSheet::new(&sprite, sprite.width / frames, sprite.height)
+        });
     }
 
+    /// False once the window is closed or `quit` is called.
     pub fn is_running(&mut self) -> bool {
-        self.running && self.window.is_open() && !self.window.is_key_down(Key::Escape)
+        self.running && self.display.is_open()
+    }
+
+    /// Ends the game loop at the next `is_running` check.
+    pub fn quit(&mut self) {
+        info!("quit requested");
+        self.running = false;
+    }
+
+    /// Whether the window has the keyboard focus.
+    pub fn is_focused(&mut self) -> bool {
+        self.display.is_focused()
+    }
+
+    pub fn is_input_captured(&self) -> bool {
+        self.input_captured
+    }
+
+    /// Gives the keyboard to a text field or menu: paddles stop responding.
+    pub fn set_input_captured(&mut self, captured: bool) {
+        if captured != self.input_captured {
+            trace!("keyboard capture changed"; captured = captured);
+        }
+        self.input_captured = captured;
+    }
+
+    /// Applies a reloaded config. Everything takes effect now except
+    /// `Config::RESTART_FIELDS`, which keep their running values; the names
+    /// of any that changed are returned. Fails, changing nothing, if the
+    /// new settings don't work with the running ones.
+    pub fn reload_config(&mut self, mut config: Config) -> Result<Vec<&'static str>, String> {
+        debug!("applying reloaded config");
+        let pending = config.keep_restart_fields(&self.config);
+        config.validate()?;
+        self.switch_config(config);
+        Ok(pending)
+    }
+
+    /// Characters typed since the last call.
+    pub fn typed_text(&mut self) -> String {
+        self.display.take_text()
+    }
+
+    /// True when `key` went down or auto-repeated this frame.
+    pub fn key_repeated(&self, key: Key) -> bool {
+        self.display.is_key_repeated(key)
+    }
+
+    /// Switches to `config`, reloading sprites and changing fullscreen if
+    /// those settings changed. Fields in `Config::RESTART_FIELDS` can't
+    /// change while running; those are reported and nothing is applied.
+    pub fn apply_config(&mut self, mut config: Config) -> Result<(), String> {
+        config.validate()?;
+        let fixed = config.keep_restart_fields(&self.config);
+        if !fixed.is_empty() {
+            return Err(format!("{} can't change while running, restart to apply", fixed.join(", ")));
+        }
+        self.switch_config(config);
+        Ok(())
+    }
+
+    /// Replaces the config, assuming the restart fields are unchanged.
+    fn switch_config(&mut self, config: Config) {
+        let old = &self.config;
+        let sprites_changed = config.asset_dir != old.asset_dir
+            || config.sprite_atlas != old.sprite_atlas
+            || config.paddle_sprite != old.paddle_sprite
+            || config.ball_sprite != old.ball_sprite
+            || config.ball_sprite_frames != old.ball_sprite_frames;
+        let fullscreen_changed = config.fullscreen != old.fullscreen;
+        self.timer.set_fps_target(config.fps_target);
+        self.timer.set_max_delta(config.max_delta_time);
+        self.config = config;
+        if fullscreen_changed {
+            self.display.set_fullscreen(self.config.fullscreen);
+        }
+        if sprites_changed {
+            debug!("sprite settings changed, reloading sprites");
+            self.load_sprites();
+        }
     }
 
     pub fn get_delta_time(&mut self) -> f32 {
         self.timer.get_delta_time()
     }
 
-    pub fn handle_input(&mut self, paddle: &mut Paddle) {
-        if paddle.is_player {
-            let mut input_velocity = 0.0;
-            if self.window.is_key_down(Key::Up) || self.window.is_key_down(Key::W) {
-                input_velocity -= self.config.paddle_speed;
+    /// Real length of the last frame, for frame rate displays.
+    pub fn frame_time(&self) -> f32 {
+        self.timer.frame_time()
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.timer.time_scale()
+    }
+
+    /// Slow motion below 1, fast-forward above.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.timer.set_time_scale(time_scale);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.timer.is_paused()
+    }
+
+    /// While paused `get_delta_time` returns 0; the game keeps drawing.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.timer.set_paused(paused);
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.set_paused(!self.is_paused());
+    }
+
+    /// Pauses and advances the simulation by `frames` target-length frames.
+    pub fn step_frames(&mut self, frames: u32) {
+        self.timer.step(frames);
+    }
+
+    /// True on the frame `key` went down.
+    pub fn key_pressed(&self, key: Key) -> bool {
+        self.display.is_key_pressed(key)
+    }
+
+    /// Moves a human paddle from the keyboard. The player uses W/S and the
+    /// arrow keys; in a versus match the arrows belong to the second player.
+    pub fn handle_input(&mut self, paddle: &mut Paddle, mode: GameMode) {
+        let (up, down) = match (paddle.is_player, paddle.is_second_player, mode) {
+            (true, _, GameMode::Single) => {
+                (self.any_key_down(&[Key::Up, Key::W]), self.any_key_down(&[Key::Down, Key::S]))
             }
-            if self.window.is_key_down(Key::Down) || self.window.is_key_down(Key::S) {
-                input_velocity += self.config.paddle_speed;
+            (true, _, GameMode::Versus) => {
+                (self.any_key_down(&[Key::W]), self.any_key_down(&[Key::S]))
             }
-            paddle.velocity_y = input_velocity;
+            (false, true, _) => (self.any_key_down(&[Key::Up]), self.any_key_down(&[Key::Down])),
+            (false, false, _) => return,
+        };
+        let mut input_velocity = 0.0;
+        if !self.input_captured && up {
+            input_velocity -= self.config.paddle_speed;
         }
+        if !self.input_captured && down {
+            input_velocity += self.config.paddle_speed;
+        }
+        paddle.velocity_y = input_velocity;
+    }
+
+    fn any_key_down(&self, keys: &[Key]) -> bool {
+        keys.iter().any(|&key| self.display.is_key_down(key))
     }
 
     pub fn clear(&mut self) {
-        self.buffer.fill(0x000000); // Black background
+        self.buffer.clear(from_rgba(self.config.background_color));
+    }
+
+    /// The frame being drawn, for the rasterizer primitives.
+    pub fn framebuffer(&self) -> &Framebuffer {
+        &self.buffer
+    }
+
+    pub fn framebuffer_mut(&mut self) -> &mut Framebuffer {
+        &mut self.buffer
     }
 
     pub fn render_paddle(&mut self, paddle: &Paddle) {
-        self.draw_rect(
-            paddle.x as usize,
-            paddle.y as usize,
-            self.config.paddle_width as usize,
-            self.config.paddle_height as usize,
-            0xFFFFFF, // White color
+        if let Some(sprite) = &self.paddle_sprite {
+            sprite.draw_scaled(
+                &mut self.buffer,
+                paddle.x as i32,
+                paddle.y as i32,
+                self.config.paddle_width as usize,
+                self.config.paddle_height as usize,
+            );
+            return;
+        }
+
+        self.buffer.fill_rect(
+            paddle.x as i32,
+            paddle.y as i32,
+            self.config.paddle_width as i32,
+            self.config.paddle_height as i32,
+            from_rgba(self.config.paddle_color),
         );
     }
 
     pub fn render_ball(&mut self, ball: &Ball) {
-        self.draw_rect(
-            (ball.x - self.config.ball_size / 2.0) as usize,
-            (ball.y - self.config.ball_size / 2.0) as usize,
-            self.config.ball_size as usize,
-            self.config.ball_size as usize,
-            0xFFFFFF, // White color
-        );
+        if let Some(sheet) = &self.ball_sprite {
+            // Step through the frames as the ball rotates
+            let turn = (ball.rotation / std::f32::consts::TAU).rem_euclid(1.0);
+            if let Some(frame) = sheet.frame((turn * sheet.len() as f32) as usize) {
+                let size = (self.config.ball_radius * 2.0) as usize;
+                frame.draw_scaled(
+                    &mut self.buffer,
+                    (ball.x - self.config.ball_radius) as i32,
+                    (ball.y - self.config.ball_radius) as i32,
+                    size,
+                    size,
+                );
+                return;
+            }
+        }
+
+        // Tint the ball by how hard it is spinning: orange for clockwise,
+        // cyan for counter-clockwise
+        let amount = (ball.spin.abs() / self.config.max_spin).min(1.0);
+        let tint = if ball.spin > 0.0 { 0xFF8800 } else { 0x00CCFF };
+        let [.., alpha] = self.config.ball_color;
+        let color = lerp_color(from_rgba(self.config.ball_color), tint, amount);
+
+        self.fill_ball_circle(ball.x, ball.y, self.config.ball_radius, with_alpha(color, alpha));
+
+        // A dark marker orbiting the centre shows the ball rotating
+        if ball.spin.abs() > 0.5 {
+            let orbit = self.config.ball_radius / 2.0;
+            let marker_x = ball.x + ball.rotation.cos() * orbit;
+            let marker_y = ball.y + ball.rotation.sin() * orbit;
+            self.buffer.fill_rect(
+                (marker_x - 1.0) as i32,
+                (marker_y - 1.0) as i32,
+                2,
+                2,
+                opaque(0x333333),
+            );
+        }
+    }
+
+    /// Ball-shaped circle, antialiased if the config asks for it.
+    fn fill_ball_circle(&mut self, cx: f32, cy: f32, radius: f32, color: u32) {
+        if self.config.ball_antialias {
+            self.buffer.fill_circle_aa(cx, cy, radius, color);
+        } else {
+            self.buffer
+                .fill_circle(cx.round() as i32, cy.round() as i32, radius.round() as i32, color);
+        }
+    }
+
+    /// Fading circles along the ball's recent path.
+    pub fn render_trail(&mut self, trail: &Trail) {
+        let count = trail.points.len() as f32;
+        // Skip the newest point, the ball itself is drawn there
+        for (i, &(x, y)) in trail.points.iter().enumerate().skip(1) {
+            let fade = 1.0 - i as f32 / count;
+            self.fill_ball_circle(
+                x,
+                y,
+                self.config.ball_radius * (0.4 + 0.6 * fade),
+                with_alpha(from_rgba(self.config.ball_color), fade * 0.35),
+            );
+        }
+    }
+
+    pub fn render_particles(&mut self, particles: &Particles) {
+        for particle in &particles.particles {
+            let progress = particle.progress();
+            let color = lerp_color(particle.start_color, particle.end_color, progress);
+            self.buffer.fill_circle_aa(
+                particle.x,
+                particle.y,
+                particle.size,
+                with_alpha(color, 1.0 - progress),
+            );
+        }
+    }
+
+    /// Draws the F3 debug overlay over the court.
+    pub fn render_debug_overlay(
+        &mut self,
+        overlay: &DebugOverlay,
+        debug: &DebugInfo,
+        balls: &ComponentStorage<Ball>,
+        paddles: &ComponentStorage<Paddle>,
+    ) {
+        overlay.draw(&mut self.buffer, &self.config, debug, balls, paddles);
+    }
+
+    /// Draws the profiler's per-phase timings in the top-right corner.
+    pub fn render_profiler(&mut self, overlay: &DebugOverlay, profiler: &Profiler) {
+        overlay.draw_profiler(&mut self.buffer, profiler);
+    }
+
+    /// Moves the finished frame by the shake offset, exposing background at
+    /// the edges. Call after everything else is drawn.
+    pub fn apply_screen_shake(&mut self, shake: &ScreenShake) {
+        let (dx, dy) = shake.offset;
+        self.buffer.shift(dx, dy, from_rgba(self.config.background_color));
     }
 
     // pub fn render_score(&mut self, score: &Score) {
@@ -114,7 +420,6 @@ impl Engine {
 
     pub fn render_score(&mut self, score: &Score) {
         let window_width = self.config.window_width as f32;
-        let window_height = self.config.window_height as f32;
 
         // Draw center line first
         self.draw_center_line();
@@ -157,7 +462,8 @@ impl Engine {
         );
 
         // Draw winning indicator if someone has high score
-        if score.player_score >= 10 || score.ai_score >= 10 {
+        let winning_score = self.config.winning_score;
+        if score.player_score >= winning_score || score.ai_score >= winning_score {
             if score.player_score > score.ai_score {
                 self.draw_text_label("WINNER!", player_score_x - 10.0, score_y + 80.0, 0x00FF00);
             } else if score.ai_score > score.player_score {
@@ -176,18 +482,13 @@ impl Engine {
 
         let mut y = 0;
         while y < window_height {
-            for dy in 0..dash_length.min(window_height - y) {
-                for dx in 0..line_width {
-                    let px = center_x - line_width / 2 + dx;
-                    let py = y + dy;
-                    if px < window_width && py < window_height {
-                        let idx = py * window_width + px;
-                        if idx < self.buffer.len() {
-                            self.buffer[idx] = 0x444444; // Dark gray
-                        }
-                    }
-                }
-            }
+            self.buffer.fill_rect(
+                (center_x - line_width / 2) as i32,
+                y as i32,
+                line_width as i32,
+                dash_length.min(window_height - y) as i32,
+                opaque(0x444444), // Dark gray
+            );
             y += dash_length + dash_gap;
         }
     }
@@ -268,22 +569,22 @@ impl Engine {
     }
 
     fn draw_horizontal_segment(&mut self, x: f32, y: f32, width: f32, thickness: f32) {
-        self.draw_rect(
-            x as usize,
-            y as usize,
-            width as usize,
-            thickness as usize,
-            0x00FFFF, // Cyan color for segments
+        self.buffer.fill_rect(
+            x as i32,
+            y as i32,
+            width as i32,
+            thickness as i32,
+            from_rgba(self.config.score_color),
         );
     }
 
     fn draw_vertical_segment(&mut self, x: f32, y: f32, height: f32, thickness: f32) {
-        self.draw_rect(
-            x as usize,
-            y as usize,
-            thickness as usize,
-            height as usize,
-            0x00FFFF, // Cyan color for segments
+        self.buffer.fill_rect(
+            x as i32,
+            y as i32,
+            thickness as i32,
+            height as i32,
+            from_rgba(self.config.score_color),
         );
     }
 
@@ -353,7 +654,7 @@ impl Engine {
             _ => vec![0; 8], // Space or unknown character
         };
 
-        for (row, &bits) in pattern.iter().enumerate() {
+        for (row, &bits) in pattern.iter().take(height).enumerate() {
             for col in 0..width {
                 if bits & (0b10000000 >> col) != 0 {
                     let px = x + col as f32;
@@ -366,32 +667,281 @@ impl Engine {
         }
     }
 
-    pub fn present(&mut self) {
-        self.window
-            .update_with_buffer(
-                &self.buffer,
-                self.config.window_width as usize,
-                self.config.window_height as usize,
-            )
-            .unwrap();
+    /// Where the logical frame sits in the window right now.
+    pub fn viewport(&self) -> Viewport {
+        Viewport::fit(
+            self.config.scale_mode,
+            (self.buffer.width(), self.buffer.height()),
+            self.display.size(),
+        )
     }
 
-    pub fn draw_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: u32) {
-        let window_width = self.config.window_width as usize;
-        let window_height = self.config.window_height as usize;
+    /// The mouse in logical coordinates, `None` outside the game area.
+    pub fn mouse_position(&self) -> Option<(f32, f32)> {
+        let (x, y) = self.display.mouse_position()?;
+        self.viewport().to_logical(x, y)
+    }
 
-        for dy in 0..height {
-            for dx in 0..width {
-                let pixel_x = x + dx;
-                let pixel_y = y + dy;
+    /// True while the left mouse button is held.
+    pub fn mouse_down(&self) -> bool {
+        self.mouse_down
+    }
 
-                if pixel_x < window_width && pixel_y < window_height {
-                    let index = pixel_y * window_width + pixel_x;
-                    if index < self.buffer.len() {
-                        self.buffer[index as usize] = color;
-                    }
-                }
+    /// True on the frame the left mouse button went down.
+    pub fn mouse_clicked(&self) -> bool {
+        self.mouse_clicked
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.display.is_fullscreen()
+    }
+
+    pub fn toggle_fullscreen(&mut self) {
+        let fullscreen = !self.display.is_fullscreen();
+        self.display.set_fullscreen(fullscreen);
+        self.config.fullscreen = self.display.is_fullscreen();
+    }
+
+    /// Shows the frame, scaled to the window. Fails if the window can't
+    /// take it; a recording that fails is stopped and logged instead.
+    pub fn present(&mut self) -> Result<(), EngineError> {
+        let viewport = self.viewport();
+        let (width, height) = self.display.size();
+        if viewport.is_identity() && (width, height) == (self.buffer.width(), self.buffer.height()) {
+            self.display.present(&self.buffer)?;
+        } else {
+            if (self.screen.width(), self.screen.height()) != (width, height) {
+                self.screen = Framebuffer::new(width, height);
+            }
+            viewport.scale_into(&self.buffer, &mut self.screen);
+            self.display.present(&self.screen)?;
+        }
+        let mouse_down = self.display.is_mouse_down();
+        self.mouse_clicked = mouse_down && !self.mouse_down;
+        self.mouse_down = mouse_down;
+
+        if let Some(recorder) = &mut self.recorder
+            && let Err(e) = recorder.capture(&self.buffer)
+        {
+            error!("recording stopped"; error = e);
+            self.recorder = None;
+            self.recording_error = Some(e);
+        }
+        Ok(())
+    }
+
+    /// Starts recording presented frames to a GIF at `path`, replacing any
+    /// recording in progress.
+    pub fn start_recording(&mut self, path: impl AsRef<Path>) -> Result<(), AssetError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| AssetError::Io(dir.into(), e))?;
+        }
+        self.recording_error = None;
+        self.recorder = Some(Recorder::start(
+            path,
+            self.buffer.width(),
+            self.buffer.height(),
+            self.config.record_scale,
+            self.config.record_frame_skip,
+            self.config.fps_target,
+        )?);
+        info!("recording started"; path = path.display());
+        Ok(())
+    }
+
+    /// Finishes the recording in progress and returns its path, or the
+    /// error that stopped it early. None if nothing was being recorded.
+    pub fn stop_recording(&mut self) -> Option<Result<PathBuf, AssetError>> {
+        let result = match self.recorder.take() {
+            Some(recorder) => Some(recorder.finish()),
+            None => self.recording_error.take().map(Err),
+        };
+        if let Some(Ok(path)) = &result {
+            info!("recording saved"; path = path.display());
+        }
+        result
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Starts a timestamped recording in `recording_dir`, or finishes the
+    /// current one, and reports which.
+    pub fn toggle_recording(&mut self) {
+        if self.is_recording() {
+            if let Some(Err(e)) = self.stop_recording() {
+                error!("failed to save recording"; error = e);
             }
+        } else if let Err(e) = self.start_timestamped_recording() {
+            error!("failed to start recording"; error = e);
         }
     }
+
+    /// Starts a recording named after the current time in `recording_dir`
+    /// and returns its path.
+    pub fn start_timestamped_recording(&mut self) -> Result<PathBuf, AssetError> {
+        let path = Path::new(&self.config.recording_dir)
+            .join(format!("recording_{}.gif", timestamp()));
+        self.start_recording(&path)?;
+        Ok(path)
+    }
+
+    /// Writes the current frame to `dir` as a timestamped PNG and returns
+    /// its path.
+    pub fn save_screenshot(&self, dir: impl AsRef<Path>) -> Result<PathBuf, AssetError> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir).map_err(|e| AssetError::Io(dir.into(), e))?;
+        let path = dir.join(format!("screenshot_{}.png", timestamp()));
+        self.buffer.save_png(&path)?;
+        Ok(path)
+    }
+
+    /// Opaque 0xRRGGBB rectangle.
+    pub fn draw_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: u32) {
+        self.buffer
+            .fill_rect(x as i32, y as i32, width as i32, height as i32, opaque(color));
+    }
+}
+
+/// The current UTC time as `YYYYMMDD_HHMMSS_mmm`, so screenshot names sort
+/// by when they were taken.
+fn timestamp() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let secs = now.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let time = secs % 86_400;
+    format!(
+        "{:04}{:02}{:02}_{:02}{:02}{:02}_{:03}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60,
+        now.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assets::load_png;
+    use crate::engine::viewport::ScaleMode;
+
+    #[test]
+    fn rejects_unusable_configs() {
+        let config = Config {
+            fps_target: 0,
+            ..Config::default()
+        };
+        assert!(matches!(
+            Engine::headless(config),
+            Err(EngineError::Config(ConfigError::Invalid(_)))
+        ));
+    }
+
+    #[test]
+    fn reloads_config_except_restart_fields() {
+        let mut engine = Engine::headless(Config::default()).unwrap();
+        let config = Config {
+            window_width: 1024,
+            ball_speed: 300.0,
+            fullscreen: true,
+            ..Config::default()
+        };
+        assert_eq!(engine.reload_config(config.clone()).unwrap(), ["window_width"]);
+        assert_eq!(engine.config.window_width, 800);
+        assert_eq!(engine.config.ball_speed, 300.0);
+        assert!(engine.is_fullscreen());
+        assert!(engine.apply_config(config).is_err());
+
+        // A paddle that only fits the new, not yet applied, window height
+        let taller = Config {
+            window_height: 1000,
+            paddle_height: 800.0,
+            ..Config::default()
+        };
+        assert!(engine.reload_config(taller).is_err());
+        assert_eq!(engine.config.paddle_height, Config::default().paddle_height);
+    }
+
+    #[test]
+    fn versus_input_and_mouse_clicks() {
+        let mut display = HeadlessDisplay::new(800, 600);
+        display.set_key_down(Key::Up, true);
+        display.set_mouse_down(true);
+        let mut engine = Engine::with_display(Config::default(), Box::new(display)).unwrap();
+        let speed = engine.config.paddle_speed;
+
+        let mut player = Paddle::new(50.0, 250.0, true);
+        let mut second = Paddle::new(750.0, 250.0, false);
+        second.is_second_player = true;
+        engine.handle_input(&mut player, GameMode::Single);
+        assert_eq!(player.velocity_y, -speed);
+        // In a versus match the arrows belong to the second player
+        engine.handle_input(&mut player, GameMode::Versus);
+        engine.handle_input(&mut second, GameMode::Versus);
+        assert_eq!((player.velocity_y, second.velocity_y), (0.0, -speed));
+
+        engine.present().unwrap();
+        assert!(engine.mouse_clicked());
+        engine.present().unwrap();
+        assert!(engine.mouse_down() && !engine.mouse_clicked());
+    }
+
+    #[test]
+    fn keyboard_focus_and_quitting() {
+        let mut display = HeadlessDisplay::new(800, 600);
+        display.set_key_down(Key::Escape, true);
+        let mut engine = Engine::with_display(Config::default(), Box::new(display)).unwrap();
+
+        // Escape is left to the pause menu
+        assert!(engine.is_running() && engine.is_focused());
+        engine.quit();
+        assert!(!engine.is_running());
+    }
+
+    #[test]
+    fn scales_to_a_resized_window_and_maps_the_mouse() {
+        let mut display = HeadlessDisplay::new(1600, 1000);
+        display.set_mouse_position(Some((800.0, 500.0)));
+        let mut engine = Engine::with_display(Config::default(), Box::new(display)).unwrap();
+        engine.clear();
+        engine.present().unwrap();
+
+        let viewport = engine.viewport();
+        assert_eq!((viewport.x, viewport.y), (133, 0));
+        assert_eq!((engine.screen.width(), engine.screen.height()), (1600, 1000));
+        let (x, y) = engine.mouse_position().unwrap();
+        assert!((x - 400.0).abs() < 1.0 && (y - 300.0).abs() < 1.0);
+
+        engine.config.scale_mode = ScaleMode::Integer;
+        assert_eq!(engine.viewport().width, 800);
+    }
+
+    #[test]
+    fn headless_screenshot_round_trips() {
+        let mut engine = Engine::headless(Config::default()).unwrap();
+        engine.clear();
+        engine.render_paddle(&Paddle::new(50.0, 250.0, true));
+        engine.render_ball(&Ball::new(400.0, 300.0, 0.0));
+        engine.present().unwrap();
+
+        let dir = std::env::temp_dir().join(format!("pong_screenshot_{}", std::process::id()));
+        let path = engine.save_screenshot(&dir).unwrap();
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("screenshot_") && name.ends_with(".png"));
+
+        let sprite = load_png(&path).unwrap();
+        let frame = engine.framebuffer();
+        assert_eq!((sprite.width, sprite.height), (frame.width(), frame.height()));
+        let expected: Vec<u32> = frame.pixels().iter().map(|&p| p | 0xFF00_0000).collect();
+        assert_eq!(sprite.pixels, expected);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
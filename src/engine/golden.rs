@@ -0,0 +1,272 @@
+// src/engine/golden.rs
+//
+// Golden-image tests. Known game states are rendered on a headless engine
+// and compared against reference PNGs in tests/golden. A mismatch writes
+// `<name>.actual.png` and `<name>.diff.png` to target/golden-diff. Run with
+// PONG_BLESS=1 to (re)write the references from the current output.
+
+use std::path::{Path, PathBuf};
+
+use crate::assets::{load_png, save_png};
+use crate::components::{Ball, DebugInfo, Intercept, Paddle, Score};
+use crate::ecs::World;
+use crate::engine::framebuffer::Framebuffer;
+use crate::engine::overlay::DebugOverlay;
+use crate::engine::{Config, Engine};
+
+const BLESS_VAR: &str = "PONG_BLESS";
+
+/// How far a frame may drift from its golden and still match.
+#[derive(Debug, Clone, Copy)]
+pub struct Tolerance {
+    pub channel: u8,           // Largest per-channel difference a pixel may have
+    pub max_mismatched: usize, // Pixels allowed to exceed `channel`
+}
+
+impl Tolerance {
+    pub const EXACT: Self = Self {
+        channel: 0,
+        max_mismatched: 0,
+    };
+}
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn diff_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("target/golden-diff")
+}
+
+fn channel_delta(a: u32, b: u32) -> u8 {
+    [16, 8, 0]
+        .iter()
+        .map(|shift| ((a >> shift) as u8).abs_diff((b >> shift) as u8))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Compares `frame` against `tests/golden/<name>.png`, panicking on a
+/// mismatch after writing the actual frame and a diff image.
+pub fn assert_golden(name: &str, frame: &Framebuffer, tolerance: Tolerance) {
+    let bless = std::env::var_os(BLESS_VAR).is_some();
+    check_golden(name, frame, tolerance, &golden_dir(), &diff_dir(), bless);
+}
+
+fn check_golden(
+    name: &str,
+    frame: &Framebuffer,
+    tolerance: Tolerance,
+    golden_dir: &Path,
+    diff_dir: &Path,
+    bless: bool,
+) {
+    let golden_path = golden_dir.join(format!("{}.png", name));
+
+    if bless {
+        std::fs::create_dir_all(golden_dir).unwrap();
+        frame.save_png(&golden_path).unwrap();
+        return;
+    }
+
+    let golden = load_png(&golden_path).unwrap_or_else(|e| {
+        panic!(
+            "no golden image for '{}' ({}), run with {}=1 to create it",
+            name, e, BLESS_VAR
+        )
+    });
+
+    let size_matches = (golden.width, golden.height) == (frame.width(), frame.height());
+    let differs = |index: usize, actual: u32| {
+        !size_matches || channel_delta(actual, golden.pixels[index]) > tolerance.channel
+    };
+    let mismatched = (0..frame.pixels().len())
+        .filter(|&index| differs(index, frame.pixels()[index]))
+        .count();
+    if mismatched <= tolerance.max_mismatched {
+        return;
+    }
+
+    std::fs::create_dir_all(diff_dir).unwrap();
+    let actual_path = diff_dir.join(format!("{}.actual.png", name));
+    frame.save_png(&actual_path).unwrap();
+
+    // Mismatched pixels in red over a dimmed copy of the actual frame
+    let mut diff = Vec::with_capacity(frame.pixels().len() * 4);
+    for (index, &actual) in frame.pixels().iter().enumerate() {
+        if differs(index, actual) {
+            diff.extend_from_slice(&[0xFF, 0x00, 0x00, 0xFF]);
+        } else {
+            let dim = |shift: u32| ((actual >> shift) as u8) / 4;
+            diff.extend_from_slice(&[dim(16), dim(8), dim(0), 0xFF]);
+        }
+    }
+    let diff_path = diff_dir.join(format!("{}.diff.png", name));
+    save_png(&diff_path, frame.width(), frame.height(), &diff).unwrap();
+
+    panic!(
+        "frame '{}' differs from its golden: {} pixels (size {}x{}, golden {}x{}), \
+         see {} and {}, or run with {}=1 if the change is intended",
+        name,
+        mismatched,
+        frame.width(),
+        frame.height(),
+        golden.width,
+        golden.height,
+        actual_path.display(),
+        diff_path.display(),
+        BLESS_VAR
+    );
+}
+
+/// A headless engine showing the two paddles, balls and score.
+fn render_scene(config: Config, balls: &[Ball], score: &Score) -> Engine {
+    let mut engine = Engine::headless(config).unwrap();
+    engine.clear();
+    engine.render_paddle(&Paddle::new(50.0, 250.0, true));
+    engine.render_paddle(&Paddle::new(750.0, 120.0, false));
+    for ball in balls {
+        engine.render_ball(ball);
+    }
+    engine.render_score(score);
+    engine
+}
+
+// Antialiased edges may move by a shade between float implementations
+const AA_TOLERANCE: Tolerance = Tolerance {
+    channel: 8,
+    max_mismatched: 16,
+};
+
+#[test]
+fn golden_serve() {
+    let engine = render_scene(
+        Config::default(),
+        &[Ball::new(400.0, 300.0, 220.0)],
+        &Score::new(),
+    );
+    assert_golden("serve", engine.framebuffer(), AA_TOLERANCE);
+}
+
+#[test]
+fn golden_rally_with_spin() {
+    let mut topspin = Ball::new(250.0, 180.0, 400.0);
+    topspin.spin = 20.0;
+    topspin.rotation = 1.0;
+    let mut backspin = Ball::new(620.0, 420.0, 400.0);
+    backspin.spin = -8.0;
+    backspin.rotation = 4.0;
+    let score = Score {
+        player_score: 3,
+        ai_score: 7,
+    };
+    let engine = render_scene(Config::default(), &[topspin, backspin], &score);
+    assert_golden("rally_with_spin", engine.framebuffer(), AA_TOLERANCE);
+}
+
+#[test]
+fn golden_aliased_ball() {
+    let config = Config {
+        ball_antialias: false,
+        ..Config::default()
+    };
+    let engine = render_scene(config, &[Ball::new(400.0, 300.0, 220.0)], &Score::new());
+    assert_golden("aliased_ball", engine.framebuffer(), Tolerance::EXACT);
+}
+
+#[test]
+fn golden_winner() {
+    // The scoreboard has a single digit per side
+    let config = Config {
+        winning_score: 9,
+        ..Config::default()
+    };
+    let score = Score {
+        player_score: config.winning_score,
+        ai_score: 4,
+    };
+    let engine = render_scene(config, &[], &score);
+    assert_golden("winner", engine.framebuffer(), Tolerance::EXACT);
+}
+
+#[test]
+fn golden_custom_colors() {
+    let config = Config {
+        ball_color: [1.0, 0.4, 0.0, 1.0],
+        paddle_color: [0.2, 0.8, 0.2, 1.0],
+        background_color: [0.05, 0.05, 0.2, 1.0],
+        score_color: [1.0, 1.0, 0.0, 1.0],
+        ..Config::default()
+    };
+    let score = Score {
+        player_score: 2,
+        ai_score: 5,
+    };
+    let engine = render_scene(config, &[Ball::new(400.0, 300.0, 220.0)], &score);
+    assert_golden("custom_colors", engine.framebuffer(), AA_TOLERANCE);
+}
+
+#[test]
+fn golden_debug_overlay() {
+    let mut ball = Ball::new(520.0, 200.0, 300.0);
+    ball.spin = 6.5;
+    let mut world = World::new();
+    world.spawn_with(ball.clone());
+    world.spawn_with(Paddle::new(50.0, 250.0, true));
+    world.spawn_with(Paddle::new(750.0, 120.0, false));
+
+    let mut debug = DebugInfo {
+        tick: 4321,
+        ..DebugInfo::default()
+    };
+    debug.add_contact(80.0, 300.0, 1.0, 0.0);
+    debug.add_contact(300.0, 0.0, 0.0, 1.0);
+    debug.contacts[1].age = 1.0;
+    debug.intercepts.push(Intercept {
+        x: 744.0,
+        y: 420.0,
+        path: vec![(520.0, 200.0), (700.0, 594.0), (744.0, 420.0)],
+    });
+
+    let mut overlay = DebugOverlay::new(true);
+    for frame in 0..150 {
+        overlay.record_frame(if frame % 40 == 0 { 0.04 } else { 1.0 / 60.0 });
+    }
+
+    let mut engine = render_scene(Config::default(), &[ball], &Score::new());
+    engine.render_debug_overlay(
+        &overlay,
+        &debug,
+        &world.components::<Ball>(),
+        &world.components::<Paddle>(),
+    );
+    assert_golden("debug_overlay", engine.framebuffer(), AA_TOLERANCE);
+}
+
+#[test]
+fn mismatch_writes_diff_images() {
+    let dir = std::env::temp_dir().join(format!("pong_golden_{}", std::process::id()));
+    let expected = Framebuffer::new(4, 4);
+    check_golden("probe", &expected, Tolerance::EXACT, &dir, &dir, true);
+
+    let mut actual = Framebuffer::new(4, 4);
+    actual.set_pixel(1, 2, 0xFFFFFFFF);
+    let result = std::panic::catch_unwind(|| {
+        check_golden("probe", &actual, Tolerance::EXACT, &dir, &dir, false)
+    });
+    assert!(result.is_err());
+
+    let diff = load_png(&dir.join("probe.diff.png")).unwrap();
+    assert_eq!(diff.pixels[2 * 4 + 1], 0xFFFF0000);
+    assert_eq!(diff.pixels[0], 0xFF000000);
+    assert!(dir.join("probe.actual.png").exists());
+
+    // A lenient enough tolerance lets the same frame pass
+    let lenient = Tolerance {
+        channel: 0,
+        max_mismatched: 1,
+    };
+    check_golden("probe", &actual, lenient, &dir, &dir, false);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,11 +1,24 @@
 
 // engine/mod.rs
+pub mod display;
+pub mod error;
+pub mod font;
+pub mod fs;
+pub mod framebuffer;
 pub mod game;
+#[cfg(test)]
+mod golden;
+pub mod overlay;
+pub mod profiler;
+pub mod recorder;
 // pub mod input;
 // pub mod renderer;
 // pub mod physics;
 pub mod time;
+pub mod viewport;
+pub mod watcher;
 pub mod config;
+pub mod date;
 
 pub use config::Config;
 pub use game::Engine;
@@ -0,0 +1,252 @@
+// src/engine/overlay.rs
+use std::collections::VecDeque;
+
+use crate::components::{Ball, DebugInfo, Paddle};
+use crate::ecs::ComponentStorage;
+use crate::engine::Config;
+use crate::engine::font::{LINE_HEIGHT, text_width};
+use crate::engine::framebuffer::{Framebuffer, opaque, with_alpha};
+use crate::engine::profiler::Profiler;
+
+const HISTORY: usize = 120; // Frames kept for the graphs
+const GRAPH_HEIGHT: i32 = 40;
+const MARGIN: i32 = 8;
+const VELOCITY_SCALE: f32 = 0.1; // Vectors show where the ball is in 0.1s
+const MAX_BALL_LINES: usize = 4;
+
+const TEXT: u32 = opaque(0xFFFFFF);
+const HITBOX: u32 = opaque(0x00FF00);
+const VELOCITY: u32 = opaque(0xFFFF00);
+const INTERCEPT: u32 = opaque(0xFF00FF);
+const CONTACT: u32 = 0xFF4040;
+const GRAPH_OK: u32 = opaque(0x00C000);
+const GRAPH_SLOW: u32 = opaque(0xFF3030);
+const GRAPH_TARGET: u32 = opaque(0x808080);
+
+/// Physics tuning overlay: frame timing graphs, the simulation tick, ball
+/// state, velocity vectors, paddle hitboxes, AI intercepts and recent
+/// collision contacts.
+pub struct DebugOverlay {
+    pub enabled: bool,
+    frame_times: VecDeque<f32>,
+}
+
+#[allow(dead_code)]
+impl DebugOverlay {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            frame_times: VecDeque::with_capacity(HISTORY),
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    pub fn record_frame(&mut self, delta_time: f32) {
+        if self.frame_times.len() == HISTORY {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(delta_time);
+    }
+
+    /// Average frame time over the history, in seconds.
+    pub fn average_frame_time(&self) -> f32 {
+        if self.frame_times.is_empty() {
+            return 0.0;
+        }
+        self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32
+    }
+
+    pub fn fps(&self) -> f32 {
+        let average = self.average_frame_time();
+        if average > 0.0 { 1.0 / average } else { 0.0 }
+    }
+
+    pub fn draw(
+        &self,
+        frame: &mut Framebuffer,
+        config: &Config,
+        debug: &DebugInfo,
+        balls: &ComponentStorage<Ball>,
+        paddles: &ComponentStorage<Paddle>,
+    ) {
+        for (_, paddle) in paddles.iter() {
+            frame.rect(
+                paddle.x.round() as i32,
+                paddle.y.round() as i32,
+                config.paddle_width as i32,
+                config.paddle_height as i32,
+                HITBOX,
+            );
+        }
+
+        for intercept in &debug.intercepts {
+            for segment in intercept.path.windows(2) {
+                let ((x0, y0), (x1, y1)) = (segment[0], segment[1]);
+                frame.line_aa(x0, y0, x1, y1, with_alpha(0xFF00FF, 0.5));
+            }
+            let (x, y) = (intercept.x.round() as i32, intercept.y.round() as i32);
+            frame.line(x - 5, y - 5, x + 5, y + 5, INTERCEPT);
+            frame.line(x - 5, y + 5, x + 5, y - 5, INTERCEPT);
+        }
+
+        for contact in &debug.contacts {
+            let alpha = 1.0 - contact.age / DebugInfo::CONTACT_LIFETIME;
+            let color = with_alpha(CONTACT, alpha);
+            let (x, y) = (contact.x.round() as i32, contact.y.round() as i32);
+            frame.circle(x, y, 4, color);
+            frame.line_aa(
+                contact.x,
+                contact.y,
+                contact.x + contact.normal_x * 14.0,
+                contact.y + contact.normal_y * 14.0,
+                color,
+            );
+        }
+
+        for (_, ball) in balls.iter() {
+            Self::draw_vector(frame, ball);
+        }
+
+        self.draw_panel(frame, config, debug, balls);
+    }
+
+    /// Per-phase timings in the top-right corner, in milliseconds over the
+    /// profiler's rolling window.
+    pub fn draw_profiler(&self, frame: &mut Framebuffer, profiler: &Profiler) {
+        if profiler.is_empty() {
+            return;
+        }
+
+        let mut lines = vec![format!(
+            "{:<10} {:>6} {:>6} {:>6} {:>6}",
+            "PHASE MS", "MIN", "AVG", "MAX", "P99"
+        )];
+        for stats in profiler.stats() {
+            lines.push(format!(
+                "{:<10} {:6.2} {:6.2} {:6.2} {:6.2}",
+                stats.name.to_uppercase(),
+                stats.min,
+                stats.avg,
+                stats.max,
+                stats.p99
+            ));
+        }
+
+        let widest = lines.iter().map(|line| text_width(line, 1)).max().unwrap_or(0) as i32;
+        let width = widest + MARGIN * 2;
+        let height = lines.len() as i32 * LINE_HEIGHT as i32 + MARGIN * 2;
+        let left = frame.width() as i32 - width - MARGIN;
+        frame.fill_rect(left, MARGIN, width, height, with_alpha(0x000000, 0.7));
+        for (index, line) in lines.iter().enumerate() {
+            let y = MARGIN * 2 + index as i32 * LINE_HEIGHT as i32;
+            frame.text(line, left + MARGIN, y, 1, TEXT);
+        }
+    }
+
+    fn draw_vector(frame: &mut Framebuffer, ball: &Ball) {
+        let end_x = ball.x + ball.velocity_x * VELOCITY_SCALE;
+        let end_y = ball.y + ball.velocity_y * VELOCITY_SCALE;
+        frame.line_aa(ball.x, ball.y, end_x, end_y, VELOCITY);
+
+        // Arrowhead, two short strokes back from the tip
+        let speed = ball.speed();
+        if speed > 0.0 {
+            let (dx, dy) = (ball.velocity_x / speed, ball.velocity_y / speed);
+            for side in [-1.0, 1.0] {
+                let back_x = end_x - dx * 6.0 - dy * 4.0 * side;
+                let back_y = end_y - dy * 6.0 + dx * 4.0 * side;
+                frame.line_aa(end_x, end_y, back_x, back_y, VELOCITY);
+            }
+        }
+    }
+
+    fn draw_panel(
+        &self,
+        frame: &mut Framebuffer,
+        config: &Config,
+        debug: &DebugInfo,
+        balls: &ComponentStorage<Ball>,
+    ) {
+        let mut lines = vec![
+            format!(
+                "FPS {:5.1}  FRAME {:5.2} MS",
+                self.fps(),
+                self.average_frame_time() * 1000.0
+            ),
+            format!("TICK {}", debug.tick),
+        ];
+        for (index, (_, ball)) in balls.iter().enumerate().take(MAX_BALL_LINES) {
+            lines.push(format!(
+                "BALL {} POS {:4.0},{:4.0} VEL {:5.0},{:5.0} SPIN {:5.1}",
+                index, ball.x, ball.y, ball.velocity_x, ball.velocity_y, ball.spin
+            ));
+        }
+
+        let text_height = lines.len() as i32 * LINE_HEIGHT as i32;
+        let widest = lines
+            .iter()
+            .map(|line| text_width(line, 1))
+            .max()
+            .unwrap_or(0);
+        let width = (HISTORY as i32 * 2 + MARGIN * 3).max(widest as i32 + MARGIN * 2);
+        let height = text_height + GRAPH_HEIGHT + MARGIN * 3;
+        let left = MARGIN;
+        let top = frame.height() as i32 - height - MARGIN;
+
+        frame.fill_rect(left, top, width, height, with_alpha(0x000000, 0.7));
+        for (index, line) in lines.iter().enumerate() {
+            let y = top + MARGIN + index as i32 * LINE_HEIGHT as i32;
+            frame.text(line, left + MARGIN, y, 1, TEXT);
+        }
+
+        // Frame time on the left, FPS on the right, newest frame rightmost
+        let graph_top = top + MARGIN * 2 + text_height;
+        let target = 1.0 / config.fps_target.max(1) as f32;
+        let slow = target * 1.1;
+        self.draw_graph(frame, left + MARGIN, graph_top, |dt| {
+            (dt / (target * 2.0), dt <= slow)
+        });
+        self.draw_graph(frame, left + MARGIN * 2 + HISTORY as i32, graph_top, |dt| {
+            let fps = if dt > 0.0 { 1.0 / dt } else { 0.0 };
+            (fps * target / 2.0, dt <= slow)
+        });
+    }
+
+    /// Bars for each recorded frame, `sample` maps a frame time to a bar
+    /// height in `0..=1` and whether it met the target. Both graphs put the
+    /// target frame rate on the grey middle line.
+    fn draw_graph(
+        &self,
+        frame: &mut Framebuffer,
+        left: i32,
+        top: i32,
+        sample: impl Fn(f32) -> (f32, bool),
+    ) {
+        let bottom = top + GRAPH_HEIGHT;
+        let offset = (HISTORY - self.frame_times.len()) as i32;
+        for (index, &delta_time) in self.frame_times.iter().enumerate() {
+            let (amount, on_target) = sample(delta_time);
+            let bar = (amount.clamp(0.0, 1.0) * GRAPH_HEIGHT as f32).round() as i32;
+            let color = if on_target { GRAPH_OK } else { GRAPH_SLOW };
+            frame.fill_rect(left + offset + index as i32, bottom - bar, 1, bar, color);
+        }
+        let middle = bottom - GRAPH_HEIGHT / 2;
+        frame.line(
+            left,
+            middle,
+            left + HISTORY as i32 - 1,
+            middle,
+            GRAPH_TARGET,
+        );
+        frame.rect(
+            left - 1,
+            top - 1,
+            HISTORY as i32 + 2,
+            GRAPH_HEIGHT + 2,
+            GRAPH_TARGET,
+        );
+    }
+}
@@ -0,0 +1,227 @@
+// src/engine/profiler.rs
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::ecs::World;
+
+const HISTORY: usize = 240; // Frames kept per phase, 4 seconds at 60 FPS
+
+/// Times a block and records it under `name` in the world's `Profiler`,
+/// evaluating to the block's value. Nothing is recorded without a
+/// `Profiler` resource.
+///
+/// ```ignore
+/// let hits = profile_scope!(world, "collision/paddles", {
+///     check_paddles(world)
+/// });
+/// ```
+#[macro_export]
+macro_rules! profile_scope {
+    ($world:expr, $name:expr, $body:block) => {{
+        let start = std::time::Instant::now();
+        let result = $body;
+        $crate::engine::profiler::Profiler::record_in($world, $name, start.elapsed());
+        result
+    }};
+}
+
+/// Summary of one phase over the recorded frames, in milliseconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseStats {
+    pub name: &'static str,
+    pub min: f32,
+    pub avg: f32,
+    pub max: f32,
+    pub p99: f32,
+    pub samples: usize,
+}
+
+/// Rolling per-phase frame timings: input, each system, render, present
+/// and anything instrumented with `profile_scope!`. Lives in the `World`
+/// as a resource; times recorded during a frame are summed per phase and
+/// committed by `end_frame`.
+pub struct Profiler {
+    phases: Vec<(&'static str, VecDeque<f32>)>, // In first-recorded order
+    current: Vec<(&'static str, f32)>,
+}
+
+#[allow(dead_code)]
+impl Profiler {
+    pub const FRAME: &'static str = "frame";
+
+    pub fn new() -> Self {
+        Self {
+            phases: Vec::new(),
+            current: Vec::new(),
+        }
+    }
+
+    /// Adds `duration` to `name` for the frame in progress.
+    pub fn record(&mut self, name: &'static str, duration: Duration) {
+        let ms = duration.as_secs_f32() * 1000.0;
+        match self.current.iter_mut().find(|(phase, _)| *phase == name) {
+            Some((_, total)) => *total += ms,
+            None => self.current.push((name, ms)),
+        }
+    }
+
+    /// Records into the world's profiler, if it has one.
+    pub fn record_in(world: &World, name: &'static str, duration: Duration) {
+        if world.has_resource::<Profiler>() {
+            world.resource_mut::<Profiler>().record(name, duration);
+        }
+    }
+
+    /// Commits the frame's phase times, plus the whole frame's `duration`
+    /// as `Profiler::FRAME`.
+    pub fn end_frame(&mut self, duration: Duration) {
+        self.record(Self::FRAME, duration);
+        for (name, ms) in self.current.drain(..) {
+            let index = match self.phases.iter().position(|(phase, _)| *phase == name) {
+                Some(index) => index,
+                None => {
+                    self.phases.push((name, VecDeque::with_capacity(HISTORY)));
+                    self.phases.len() - 1
+                }
+            };
+            let samples = &mut self.phases[index].1;
+            if samples.len() == HISTORY {
+                samples.pop_front();
+            }
+            samples.push_back(ms);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.phases.clear();
+        self.current.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.phases.is_empty()
+    }
+
+    pub fn phase(&self, name: &str) -> Option<PhaseStats> {
+        self.phases
+            .iter()
+            .find(|(phase, _)| *phase == name)
+            .map(|(phase, samples)| Self::summarize(phase, samples))
+    }
+
+    /// Every phase in the order it was first recorded.
+    pub fn stats(&self) -> Vec<PhaseStats> {
+        self.phases
+            .iter()
+            .map(|(name, samples)| Self::summarize(name, samples))
+            .collect()
+    }
+
+    fn summarize(name: &'static str, samples: &VecDeque<f32>) -> PhaseStats {
+        let mut sorted: Vec<f32> = samples.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        let count = sorted.len();
+        // Nearest-rank percentile
+        let p99_rank = (count as f32 * 0.99).ceil() as usize;
+        PhaseStats {
+            name,
+            min: sorted.first().copied().unwrap_or(0.0),
+            avg: sorted.iter().sum::<f32>() / count.max(1) as f32,
+            max: sorted.last().copied().unwrap_or(0.0),
+            p99: sorted
+                .get(p99_rank.saturating_sub(1))
+                .copied()
+                .unwrap_or(0.0),
+            samples: count,
+        }
+    }
+
+    /// The stats as CSV, one row per phase, times in milliseconds.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("phase,min_ms,avg_ms,max_ms,p99_ms,samples\n");
+        for stats in self.stats() {
+            let _ = writeln!(
+                csv,
+                "{},{:.4},{:.4},{:.4},{:.4},{}",
+                stats.name, stats.min, stats.avg, stats.max, stats.p99, stats.samples
+            );
+        }
+        csv
+    }
+
+    pub fn save_csv(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_csv())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn summarizes_phases() {
+        let mut profiler = Profiler::new();
+        for frame in 1..=100 {
+            profiler.record("render", ms(frame));
+            // Recorded twice in a frame, the times add up
+            profiler.record("input", ms(1));
+            profiler.record("input", ms(1));
+            profiler.end_frame(ms(frame + 2));
+        }
+
+        let render = profiler.phase("render").unwrap();
+        assert_eq!((render.min, render.max, render.samples), (1.0, 100.0, 100));
+        assert!((render.avg - 50.5).abs() < 1e-3);
+        assert_eq!(render.p99, 99.0);
+        assert_eq!(profiler.phase("input").unwrap().max, 2.0);
+
+        let names: Vec<_> = profiler.stats().iter().map(|stats| stats.name).collect();
+        assert_eq!(names, ["render", "input", Profiler::FRAME]);
+        assert!(profiler.phase("physics").is_none());
+    }
+
+    #[test]
+    fn keeps_a_rolling_window() {
+        let mut profiler = Profiler::new();
+        for _ in 0..HISTORY {
+            profiler.end_frame(ms(50));
+        }
+        for _ in 0..HISTORY {
+            profiler.end_frame(ms(10));
+        }
+        let frame = profiler.phase(Profiler::FRAME).unwrap();
+        assert_eq!((frame.max, frame.samples), (10.0, HISTORY));
+    }
+
+    #[test]
+    fn scopes_record_into_the_world() {
+        let mut world = World::new();
+        let value = profile_scope!(&world, "work", { 6 * 7 });
+        assert_eq!(value, 42);
+
+        world.insert_resource(Profiler::new());
+        profile_scope!(&world, "work", {});
+        world.resource_mut::<Profiler>().end_frame(ms(1));
+        assert_eq!(
+            world.resource::<Profiler>().phase("work").unwrap().samples,
+            1
+        );
+
+        let csv = world.resource::<Profiler>().to_csv();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("phase,min_ms,avg_ms,max_ms,p99_ms,samples")
+        );
+        assert!(lines.next().unwrap().starts_with("work,"));
+        assert!(lines.next().unwrap().starts_with("frame,1.0000,"));
+    }
+}
@@ -0,0 +1,148 @@
+// src/engine/recorder.rs
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use crate::assets::AssetError;
+use crate::engine::framebuffer::Framebuffer;
+
+/// Streams framebuffer frames into an animated GIF. Every `frame_skip + 1`th
+/// frame is kept, downscaled by `scale` with nearest-neighbour sampling.
+pub struct Recorder {
+    path: PathBuf,
+    encoder: gif::Encoder<BufWriter<File>>,
+    width: usize,
+    height: usize,
+    frame_skip: u32,
+    frame_time: f64, // Seconds per presented frame
+    frames_seen: u64,
+    frames_written: u64,
+    elapsed_cs: u64, // Timestamp of the last written frame, in GIF centiseconds
+}
+
+#[allow(dead_code)]
+impl Recorder {
+    pub fn start(
+        path: &Path,
+        source_width: usize,
+        source_height: usize,
+        scale: f32,
+        frame_skip: u32,
+        fps: u64,
+    ) -> Result<Self, AssetError> {
+        let scale = scale.clamp(0.05, 1.0);
+        let width = ((source_width as f32 * scale).round() as usize).clamp(1, u16::MAX as usize);
+        let height = ((source_height as f32 * scale).round() as usize).clamp(1, u16::MAX as usize);
+
+        let gif_error = |e| AssetError::Gif(path.into(), e);
+        let file = File::create(path).map_err(|e| AssetError::Io(path.into(), e))?;
+        let mut encoder = gif::Encoder::new(BufWriter::new(file), width as u16, height as u16, &[])
+            .map_err(gif_error)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(gif_error)?;
+
+        Ok(Self {
+            path: path.into(),
+            encoder,
+            width,
+            height,
+            frame_skip,
+            frame_time: 1.0 / fps.max(1) as f64,
+            frames_seen: 0,
+            frames_written: 0,
+            elapsed_cs: 0,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn frames_written(&self) -> u64 {
+        self.frames_written
+    }
+
+    /// Offers a presented frame to the recording, which keeps it or skips it.
+    pub fn capture(&mut self, framebuffer: &Framebuffer) -> Result<(), AssetError> {
+        let index = self.frames_seen;
+        self.frames_seen += 1;
+        if !index.is_multiple_of(self.frame_skip as u64 + 1) {
+            return Ok(());
+        }
+
+        // GIF delays are whole centiseconds, round the running total rather
+        // than each delay so long recordings don't drift
+        let step = self.frame_skip as f64 + 1.0;
+        let end_cs = ((index as f64 + step) * self.frame_time * 100.0).round() as u64;
+        let delay = end_cs
+            .saturating_sub(self.elapsed_cs)
+            .clamp(1, u16::MAX as u64) as u16;
+        self.elapsed_cs = end_cs;
+
+        let mut rgba = self.downscale(framebuffer);
+        let mut frame =
+            gif::Frame::from_rgba_speed(self.width as u16, self.height as u16, &mut rgba, 10);
+        frame.delay = delay;
+        self.encoder
+            .write_frame(&frame)
+            .map_err(|e| AssetError::Gif(self.path.clone(), e))?;
+        self.frames_written += 1;
+        Ok(())
+    }
+
+    /// Writes the GIF trailer and returns the file's path.
+    pub fn finish(self) -> Result<PathBuf, AssetError> {
+        self.encoder
+            .into_inner()
+            .map_err(|e| AssetError::Io(self.path.clone(), e))?;
+        Ok(self.path)
+    }
+
+    fn downscale(&self, framebuffer: &Framebuffer) -> Vec<u8> {
+        let pixels = framebuffer.pixels();
+        let mut rgba = Vec::with_capacity(self.width * self.height * 4);
+        for y in 0..self.height {
+            let source_y = y * framebuffer.height() / self.height;
+            for x in 0..self.width {
+                let source_x = x * framebuffer.width() / self.width;
+                let p = pixels[source_y * framebuffer.width() + source_x];
+                rgba.extend_from_slice(&[(p >> 16) as u8, (p >> 8) as u8, p as u8, 0xFF]);
+            }
+        }
+        rgba
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_every_other_frame_at_half_size() {
+        let path = std::env::temp_dir().join(format!("pong_record_{}.gif", std::process::id()));
+        let mut framebuffer = Framebuffer::new(8, 4);
+        let mut recorder = Recorder::start(&path, 8, 4, 0.5, 1, 60).unwrap();
+        for frame in 0..6 {
+            framebuffer.clear(if frame % 2 == 0 { 0xFF0000 } else { 0x0000FF });
+            recorder.capture(&framebuffer).unwrap();
+        }
+        assert_eq!(recorder.frames_written(), 3);
+        recorder.finish().unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(File::open(&path).unwrap()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (4, 2));
+
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(&frame.buffer[0..4], &[0xFF, 0x00, 0x00, 0xFF]);
+            delays.push(frame.delay);
+        }
+        // Two 60 Hz frames per GIF frame: 3.33 cs each, rounded without drift
+        assert_eq!(delays, vec![3, 4, 3]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,11 +1,18 @@
 // src/engine/time.rs
 use std::time::{Duration, Instant};
 
+use crate::{debug, info};
+
 
 pub struct Timer {
     last_frame: Instant,
     target_frame_time: Duration,
     // delta: Duration,
+    time_scale: f32,  // Simulation seconds per real second
+    frame_time: f32,  // Unscaled length of the last frame
+    max_delta: f32,   // Longer frames are cut to this, in real seconds
+    paused: bool,
+    steps: u32,       // Frames to advance while paused
 }
 
 
@@ -14,10 +21,19 @@ impl Timer {
     pub fn new(fps_target: u64) -> Self {
         Self {
             last_frame: Instant::now(),
-            target_frame_time: Duration::from_nanos(1_000_000_000u64 / fps_target),
+            target_frame_time: Duration::from_nanos(1_000_000_000u64 / fps_target.max(1)),
+            time_scale: 1.0,
+            frame_time: 0.0,
+            max_delta: 0.1,
+            paused: false,
+            steps: 0,
         }
     }
 
+    pub fn set_fps_target(&mut self, fps_target: u64) {
+        self.target_frame_time = Duration::from_nanos(1_000_000_000u64 / fps_target.max(1));
+    }
+
     pub fn delta_time(&mut self) -> f32 {
         let now = Instant::now();
         let delta = now.duration_since(self.last_frame);
@@ -29,10 +45,132 @@ impl Timer {
         self.last_frame.elapsed() >= self.target_frame_time
     }
 
+    /// Simulation time for this frame: the time since the last call,
+    /// clamped and scaled, or 0 while paused. See `advance`.
     pub fn get_delta_time(&mut self) -> f32 {
         let now = Instant::now();
         let delta = now.duration_since(self.last_frame);
         self.last_frame = now;
-        delta.as_secs_f32()
+        self.advance(delta.as_secs_f32())
+    }
+
+    /// Turns a frame that took `real_delta` seconds into simulation time.
+    /// Spikes, like the stall while a window is dragged, are cut to the
+    /// max delta so objects don't jump. While paused this is 0, except for
+    /// frames queued by `step`, which last one target frame.
+    pub fn advance(&mut self, real_delta: f32) -> f32 {
+        self.frame_time = real_delta;
+        if self.paused {
+            if self.steps == 0 {
+                return 0.0;
+            }
+            self.steps -= 1;
+            return self.target_frame_time.as_secs_f32() * self.time_scale;
+        }
+        if real_delta > self.max_delta {
+            debug!(
+                "long frame clamped";
+                frame_ms = real_delta * 1000.0,
+                max_ms = self.max_delta * 1000.0
+            );
+        }
+        real_delta.min(self.max_delta) * self.time_scale
+    }
+
+    /// Real length of the last frame, whatever the time scale.
+    pub fn frame_time(&self) -> f32 {
+        self.frame_time
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        debug!("time scale changed"; time_scale = time_scale);
+        self.time_scale = time_scale.max(0.0);
+    }
+
+    pub fn max_delta(&self) -> f32 {
+        self.max_delta
+    }
+
+    pub fn set_max_delta(&mut self, max_delta: f32) {
+        self.max_delta = max_delta.max(0.0);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pausing freezes simulation time; resuming drops any queued steps.
+    pub fn set_paused(&mut self, paused: bool) {
+        if paused != self.paused {
+            info!("simulation {}", if paused { "paused" } else { "resumed" });
+        }
+        self.paused = paused;
+        self.steps = 0;
+    }
+
+    /// Pauses if needed and queues `frames` single frames to advance.
+    pub fn step(&mut self, frames: u32) {
+        self.paused = true;
+        self.steps = self.steps.saturating_add(frames);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_and_clamps_delta() {
+        let mut timer = Timer::new(60);
+        assert_eq!(timer.advance(0.02), 0.02);
+
+        timer.set_time_scale(0.5);
+        assert_eq!(timer.advance(0.02), 0.01);
+        assert_eq!(timer.frame_time(), 0.02);
+
+        // A two second stall counts as one max-length frame
+        timer.set_max_delta(0.05);
+        assert_eq!(timer.advance(2.0), 0.025);
+    }
+
+    #[test]
+    fn pauses_and_steps() {
+        let mut timer = Timer::new(50);
+        timer.set_paused(true);
+        assert_eq!(timer.advance(0.02), 0.0);
+
+        timer.step(2);
+        assert_eq!(timer.advance(0.5), 0.02);
+        assert_eq!(timer.advance(0.5), 0.02);
+        assert_eq!(timer.advance(0.5), 0.0);
+        assert!(timer.is_paused());
+
+        timer.step(u32::MAX);
+        timer.step(1);
+        timer.set_paused(false);
+        assert_eq!(timer.advance(0.01), 0.01);
+    }
+
+    #[test]
+    fn logs_pauses_and_clamped_frames() {
+        let mut timer = Timer::new(60);
+        let ((), records) = crate::log::capture(|| {
+            timer.set_paused(true);
+            timer.set_paused(true);
+            timer.set_paused(false);
+            timer.advance(1.0);
+        });
+
+        let messages: Vec<_> = records.iter().map(|record| record.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["simulation paused", "simulation resumed", "long frame clamped"]
+        );
+        assert_eq!(records[2].level, crate::log::Level::Debug);
+        assert_eq!(records[2].field("frame_ms"), Some("1000"));
     }
 }
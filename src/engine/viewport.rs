@@ -0,0 +1,185 @@
+// src/engine/viewport.rs
+//
+// Maps the fixed logical resolution the game is drawn at onto the window,
+// whatever size the window currently is.
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::framebuffer::Framebuffer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScaleMode {
+    /// Largest whole-number scale that fits, centred. Falls back to
+    /// letterboxing when the window is smaller than the logical size.
+    Integer,
+    /// Fill the whole window, ignoring aspect ratio.
+    Stretch,
+    /// Largest scale that keeps the aspect ratio, with black bars.
+    Letterbox,
+}
+
+impl ScaleMode {
+    pub fn next(self) -> Self {
+        match self {
+            ScaleMode::Integer => ScaleMode::Stretch,
+            ScaleMode::Stretch => ScaleMode::Letterbox,
+            ScaleMode::Letterbox => ScaleMode::Integer,
+        }
+    }
+}
+
+/// The rectangle of the window the logical frame is drawn into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    logical_width: usize,
+    logical_height: usize,
+}
+
+#[allow(dead_code)]
+impl Viewport {
+    pub fn fit(mode: ScaleMode, logical: (usize, usize), window: (usize, usize)) -> Self {
+        let (logical_width, logical_height) = (logical.0.max(1), logical.1.max(1));
+        let (window_width, window_height) = (window.0.max(1), window.1.max(1));
+        let fit_scale = (window_width as f32 / logical_width as f32)
+            .min(window_height as f32 / logical_height as f32);
+
+        let (width, height) = match mode {
+            ScaleMode::Stretch => (window_width, window_height),
+            ScaleMode::Integer if fit_scale >= 1.0 => {
+                let scale = fit_scale.floor() as usize;
+                (logical_width * scale, logical_height * scale)
+            }
+            ScaleMode::Integer | ScaleMode::Letterbox => (
+                ((logical_width as f32 * fit_scale).round() as usize).clamp(1, window_width),
+                ((logical_height as f32 * fit_scale).round() as usize).clamp(1, window_height),
+            ),
+        };
+
+        Self {
+            x: (window_width - width) / 2,
+            y: (window_height - height) / 2,
+            width,
+            height,
+            logical_width,
+            logical_height,
+        }
+    }
+
+    /// True when the logical frame can be shown as is.
+    pub fn is_identity(&self) -> bool {
+        self.x == 0
+            && self.y == 0
+            && self.width == self.logical_width
+            && self.height == self.logical_height
+    }
+
+    /// Window coordinates to logical ones, `None` over the bars.
+    pub fn to_logical(self, x: f32, y: f32) -> Option<(f32, f32)> {
+        let local_x = x - self.x as f32;
+        let local_y = y - self.y as f32;
+        if local_x < 0.0
+            || local_y < 0.0
+            || local_x >= self.width as f32
+            || local_y >= self.height as f32
+        {
+            return None;
+        }
+        Some((
+            local_x * self.logical_width as f32 / self.width as f32,
+            local_y * self.logical_height as f32 / self.height as f32,
+        ))
+    }
+
+    /// Draws `source` into `target` with nearest-neighbour sampling, filling
+    /// the rest of `target` black.
+    pub fn scale_into(&self, source: &Framebuffer, target: &mut Framebuffer) {
+        target.clear(0x000000);
+        let target_width = target.width();
+        let width = self.width.min(target_width.saturating_sub(self.x));
+        let height = self.height.min(target.height().saturating_sub(self.y));
+        let columns: Vec<usize> = (0..width)
+            .map(|x| x * source.width() / self.width)
+            .collect();
+
+        let pixels = target.pixels_mut();
+        for y in 0..height {
+            let source_y = y * source.height() / self.height;
+            let source_row = &source.pixels()[source_y * source.width()..][..source.width()];
+            let start = (self.y + y) * target_width + self.x;
+            for (pixel, &source_x) in pixels[start..start + width].iter_mut().zip(&columns) {
+                *pixel = source_row[source_x];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fits_each_mode() {
+        let logical = (800, 600);
+        let stretch = Viewport::fit(ScaleMode::Stretch, logical, (1000, 500));
+        assert_eq!(
+            (stretch.x, stretch.y, stretch.width, stretch.height),
+            (0, 0, 1000, 500)
+        );
+
+        let letterbox = Viewport::fit(ScaleMode::Letterbox, logical, (1920, 1080));
+        assert_eq!(
+            (letterbox.x, letterbox.y, letterbox.width, letterbox.height),
+            (240, 0, 1440, 1080)
+        );
+
+        let integer = Viewport::fit(ScaleMode::Integer, logical, (1920, 1080));
+        assert_eq!(
+            (integer.x, integer.y, integer.width, integer.height),
+            (560, 240, 800, 600)
+        );
+        let integer = Viewport::fit(ScaleMode::Integer, logical, (1700, 1300));
+        assert_eq!((integer.width, integer.height), (1600, 1200));
+
+        // Smaller than the logical size, integer scaling shrinks like letterbox
+        let small = Viewport::fit(ScaleMode::Integer, logical, (400, 400));
+        assert_eq!(
+            (small.x, small.y, small.width, small.height),
+            (0, 50, 400, 300)
+        );
+
+        assert!(Viewport::fit(ScaleMode::Integer, logical, logical).is_identity());
+    }
+
+    #[test]
+    fn maps_window_to_logical_coordinates() {
+        let viewport = Viewport::fit(ScaleMode::Letterbox, (800, 600), (1920, 1080));
+        assert_eq!(viewport.to_logical(240.0, 0.0), Some((0.0, 0.0)));
+        assert_eq!(viewport.to_logical(960.0, 540.0), Some((400.0, 300.0)));
+        assert_eq!(viewport.to_logical(100.0, 540.0), None);
+        assert_eq!(viewport.to_logical(1680.0, 540.0), None);
+    }
+
+    #[test]
+    fn scales_with_nearest_neighbour_and_bars() {
+        let mut source = Framebuffer::new(2, 1);
+        source.pixels_mut().copy_from_slice(&[0x111111, 0x222222]);
+        let viewport = Viewport::fit(ScaleMode::Integer, (2, 1), (5, 4));
+        let mut target = Framebuffer::new(5, 4);
+        target.clear(0xFFFFFF);
+        viewport.scale_into(&source, &mut target);
+
+        #[rustfmt::skip]
+        let expected = [
+            0, 0, 0, 0, 0,
+            0x111111, 0x111111, 0x222222, 0x222222, 0,
+            0x111111, 0x111111, 0x222222, 0x222222, 0,
+            0, 0, 0, 0, 0,
+        ];
+        assert_eq!(target.pixels(), &expected);
+    }
+}
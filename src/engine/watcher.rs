@@ -0,0 +1,101 @@
+// src/engine/watcher.rs
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::engine::Config;
+use crate::engine::config::ConfigError;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Watches a config file by polling its modification time, so edits can
+/// be applied while the game runs.
+pub struct ConfigWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>, // As of the last check, None if missing
+    last_poll: Instant,
+}
+
+#[allow(dead_code)]
+impl ConfigWatcher {
+    /// Starts watching `path`. The file as it is now counts as seen, and
+    /// it doesn't have to exist yet.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            modified: modified_time(&path),
+            path,
+            last_poll: Instant::now(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Checks the file at most every `POLL_INTERVAL`, see `check`.
+    pub fn poll(&mut self) -> Option<Result<Config, ConfigError>> {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return None;
+        }
+        self.last_poll = Instant::now();
+        self.check()
+    }
+
+    /// Loads the file if it changed since the last check. A file that was
+    /// deleted is ignored until it comes back.
+    pub fn check(&mut self) -> Option<Result<Config, ConfigError>> {
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        modified.map(|_| Config::load(&self.path))
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    fn write(path: &Path, text: &str, seconds: u64) {
+        std::fs::write(path, text).unwrap();
+        // Explicit times, since quick writes can share a timestamp
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(seconds);
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    #[test]
+    fn reloads_changed_files() {
+        let dir = std::env::temp_dir().join(format!("pong_watcher_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json");
+        let _ = std::fs::remove_file(&path);
+
+        let mut watcher = ConfigWatcher::new(&path);
+        assert!(watcher.check().is_none());
+
+        write(&path, r#"{ "paddle_speed": 420 }"#, 1_000);
+        let config = watcher.check().unwrap().unwrap();
+        assert_eq!(config.paddle_speed, 420.0);
+        assert!(watcher.check().is_none());
+
+        write(&path, r#"{ "paddle_speed": "#, 2_000);
+        assert!(watcher.check().unwrap().is_err());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(watcher.check().is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
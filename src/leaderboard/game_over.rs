@@ -0,0 +1,280 @@
+// src/leaderboard/game_over.rs
+use minifb::Key;
+
+use crate::components::GameMode;
+use crate::ecs::World;
+use crate::engine::config::AiDifficulty;
+use crate::engine::font::{LINE_HEIGHT, text_width};
+use crate::engine::framebuffer::{Framebuffer, opaque, with_alpha};
+use crate::engine::{Config, Engine};
+use crate::leaderboard::{Entry, Leaderboard, Metric};
+use crate::{error, info};
+
+const MAX_INITIALS: usize = 3;
+const MARGIN: i32 = 16;
+const TITLE_SCALE: usize = 3;
+const TEXT_SCALE: usize = 2;
+
+const WIN: u32 = opaque(0x40FF40);
+const LOSS: u32 = opaque(0xFF6040);
+const TEXT: u32 = opaque(0xFFFFFF);
+const DIM: u32 = opaque(0xA0A0A0);
+const HIGHLIGHT: u32 = opaque(0xFFFF80);
+const EDGE: u32 = opaque(0x606080);
+
+/// The screen shown once a match is won: the result, the leaderboard and,
+/// if the match made the board, a prompt for the player's initials. Versus
+/// matches only show the result.
+pub struct GameOver {
+    entry: Entry,
+    player_won: bool,
+    versus: bool,
+    rank: Option<usize>, // Place on the board, highlighted in the table
+    entering: bool,      // Typing initials
+    metric: Metric,      // The board's settings when the match ended
+    difficulty: Option<AiDifficulty>,
+    size: usize,
+}
+
+#[allow(dead_code)]
+impl GameOver {
+    /// Ranks `entry` on the world's `Leaderboard` as `config` sets it up.
+    /// Without a leaderboard, or after a versus match, nothing is entered.
+    pub fn new(entry: Entry, player_won: bool, world: &World, config: &Config) -> Self {
+        let versus = GameMode::of(world) == GameMode::Versus;
+        let (metric, difficulty, size) = (
+            config.leaderboard_metric,
+            config.leaderboard_difficulty,
+            config.leaderboard_size,
+        );
+        let rank = if world.has_resource::<Leaderboard>() && !versus {
+            world
+                .resource::<Leaderboard>()
+                .rank(&entry, metric, difficulty, size)
+        } else {
+            None
+        };
+        Self {
+            entry,
+            player_won,
+            versus,
+            rank,
+            entering: rank.is_some(),
+            metric,
+            difficulty,
+            size,
+        }
+    }
+
+    pub fn is_entering(&self) -> bool {
+        self.entering
+    }
+
+    pub fn initials(&self) -> &str {
+        &self.entry.initials
+    }
+
+    /// Adds letters and digits, upper-cased, up to three.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars().filter(char::is_ascii_alphanumeric) {
+            if self.entry.initials.len() < MAX_INITIALS {
+                self.entry.initials.push(c.to_ascii_uppercase());
+            }
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        self.entry.initials.pop();
+    }
+
+    /// Puts the entry on the board under the typed initials. With none
+    /// typed the match is left off.
+    pub fn submit(&mut self, board: &mut Leaderboard) {
+        self.entering = false;
+        if self.entry.initials.is_empty() {
+            self.rank = None;
+            return;
+        }
+        board.insert(self.entry.clone(), self.size);
+    }
+
+    /// Applies this frame's keys, saving the board when initials are
+    /// entered. Returns true once the player asks for the next match. Call
+    /// after `Engine::present`, when key state is current.
+    pub fn update(&mut self, engine: &mut Engine, world: &mut World) -> bool {
+        let text = engine.typed_text();
+        if !self.entering {
+            engine.set_input_captured(false);
+            return engine.key_pressed(Key::Enter) || engine.key_pressed(Key::Space);
+        }
+
+        engine.set_input_captured(true);
+        self.type_text(&text);
+        if engine.key_repeated(Key::Backspace) {
+            self.backspace();
+        }
+        if engine.key_pressed(Key::Enter) {
+            let mut board = world.resource_mut::<Leaderboard>();
+            self.submit(&mut board);
+            if self.rank.is_some() {
+                let path = &engine.config.leaderboard_file;
+                match board.save(path) {
+                    Ok(()) => info!(
+                        "leaderboard entry added";
+                        initials = self.entry.initials,
+                        place = self.rank.unwrap_or(0) + 1
+                    ),
+                    Err(e) => error!("failed to save leaderboard"; error = e),
+                }
+            }
+            engine.set_input_captured(false);
+        }
+        false
+    }
+
+    /// The table rows: the ranked entries, with the one being entered
+    /// shown in its place.
+    fn rows(&self, board: &Leaderboard) -> Vec<Entry> {
+        let mut rows: Vec<Entry> = board
+            .ranked(self.metric, self.difficulty)
+            .into_iter()
+            .cloned()
+            .collect();
+        if let (true, Some(rank)) = (self.entering, self.rank) {
+            let mut pending = self.entry.clone();
+            pending.initials = format!("{:_<3}", pending.initials);
+            rows.insert(rank.min(rows.len()), pending);
+        }
+        rows.truncate(self.size);
+        rows
+    }
+
+    pub fn draw(&self, frame: &mut Framebuffer, world: &World) {
+        let (title, title_color) = match (self.versus, self.player_won) {
+            (false, true) => ("YOU WIN!", WIN),
+            (false, false) => ("AI WINS", LOSS),
+            (true, true) => ("PLAYER 1 WINS", WIN),
+            (true, false) => ("PLAYER 2 WINS", WIN),
+        };
+        let mut lines = vec![(
+            format!("{} - {}", self.entry.player_score, self.entry.ai_score),
+            TEXT,
+        )];
+
+        if world.has_resource::<Leaderboard>() && !self.versus {
+            let metric = self.metric;
+            let heading = match self.difficulty {
+                Some(difficulty) => format!(
+                    "BEST {} VS {}",
+                    metric.name().replace('_', " ").to_uppercase(),
+                    difficulty.name().to_uppercase()
+                ),
+                None => format!("BEST {}", metric.name().replace('_', " ").to_uppercase()),
+            };
+            lines.push((String::new(), TEXT));
+            lines.push((heading, DIM));
+            let board = world.resource::<Leaderboard>();
+            for (index, row) in self.rows(&board).iter().enumerate() {
+                let color = if Some(index) == self.rank {
+                    HIGHLIGHT
+                } else {
+                    TEXT
+                };
+                lines.push((
+                    format!(
+                        "{:>2}. {:<3} {:>9} {:<6} {}",
+                        index + 1,
+                        row.initials,
+                        metric.format(row),
+                        row.difficulty.name().to_uppercase(),
+                        row.date
+                    ),
+                    color,
+                ));
+            }
+        }
+
+        lines.push((String::new(), TEXT));
+        let footer = if self.entering {
+            "TYPE YOUR INITIALS AND PRESS ENTER"
+        } else {
+            "PRESS ENTER TO PLAY AGAIN"
+        };
+        lines.push((footer.to_string(), DIM));
+
+        let line_height = (LINE_HEIGHT * TEXT_SCALE) as i32;
+        let title_height = (LINE_HEIGHT * TITLE_SCALE) as i32;
+        let widest = lines
+            .iter()
+            .map(|(line, _)| text_width(line, TEXT_SCALE))
+            .chain([text_width(title, TITLE_SCALE)])
+            .max()
+            .unwrap_or(0) as i32;
+        let width = widest + MARGIN * 2;
+        let height = title_height + lines.len() as i32 * line_height + MARGIN * 3;
+        let left = (frame.width() as i32 - width) / 2;
+        let top = (frame.height() as i32 - height) / 2;
+
+        frame.fill_rect(left, top, width, height, with_alpha(0x101018, 0.9));
+        frame.rect(left, top, width, height, EDGE);
+        let title_left = left + (width - text_width(title, TITLE_SCALE) as i32) / 2;
+        frame.text(title, title_left, top + MARGIN, TITLE_SCALE, title_color);
+        for (index, (line, color)) in lines.iter().enumerate() {
+            let y = top + MARGIN * 2 + title_height + index as i32 * line_height;
+            frame.text(line, left + MARGIN, y, TEXT_SCALE, *color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{MatchStats, Score};
+    use crate::engine::display::HeadlessDisplay;
+
+    fn finished_match(player_score: u32, ai_score: u32) -> Entry {
+        let score = Score {
+            player_score,
+            ai_score,
+        };
+        Entry::new(&score, &MatchStats::new(), AiDifficulty::Normal)
+    }
+
+    #[test]
+    fn prompts_for_initials_on_a_high_score() {
+        let dir = std::env::temp_dir().join(format!("pong_game_over_{}", std::process::id()));
+        let config = Config {
+            leaderboard_file: dir.join("leaderboard.json").to_string_lossy().into_owned(),
+            leaderboard_size: 1,
+            ..Config::default()
+        };
+        let mut display = HeadlessDisplay::new(800, 600);
+        display.type_text("ab!c9d");
+        let mut engine = Engine::with_display(config.clone(), Box::new(display)).unwrap();
+        let mut world = World::new();
+        world.insert_resource(Leaderboard::new());
+
+        let mut screen = GameOver::new(finished_match(10, 4), true, &world, &config);
+        assert!(screen.is_entering());
+        assert!(!screen.update(&mut engine, &mut world));
+        assert_eq!(screen.initials(), "ABC");
+        assert!(engine.is_input_captured());
+        screen.backspace();
+        screen.submit(&mut world.resource_mut::<Leaderboard>());
+        assert!(!screen.is_entering());
+        assert_eq!(world.resource::<Leaderboard>().entries[0].initials, "AB");
+
+        // A worse match doesn't make a board of one
+        let screen = GameOver::new(finished_match(10, 8), true, &world, &config);
+        assert!(!screen.is_entering());
+        world.insert_resource(GameMode::Versus);
+        assert!(!GameOver::new(finished_match(10, 0), true, &world, &config).is_entering());
+        world.insert_resource(GameMode::Single);
+
+        let mut frame = Framebuffer::new(800, 600);
+        screen.draw(&mut frame, &world);
+        assert!(frame.pixels().contains(&(WIN & 0xFFFFFF)));
+        assert!(!frame.pixels().contains(&(HIGHLIGHT & 0xFFFFFF)));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
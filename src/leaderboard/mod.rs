@@ -0,0 +1,379 @@
+// src/leaderboard/mod.rs
+pub mod game_over;
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::{MatchStats, Score};
+use crate::engine::config::AiDifficulty;
+use crate::engine::date::today;
+use crate::engine::fs::{atomic_write, with_suffix};
+use crate::warn;
+
+pub use game_over::GameOver;
+
+/// Version written to leaderboard files.
+pub const VERSION: u32 = 1;
+
+/// What the leaderboard is ranked by, highest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    Margin,       // Player's points minus the AI's, negative for a loss
+    LongestRally, // Most paddle hits between two points
+}
+
+impl Metric {
+    pub const ALL: [Metric; 2] = [Metric::Margin, Metric::LongestRally];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Metric::Margin => "margin",
+            Metric::LongestRally => "longest_rally",
+        }
+    }
+
+    /// The entry's value as shown in the table.
+    pub fn format(self, entry: &Entry) -> String {
+        match self {
+            Metric::Margin => format!("{:+}", entry.margin()),
+            Metric::LongestRally => format!("{} HITS", entry.longest_rally),
+        }
+    }
+}
+
+/// One finished match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub initials: String,
+    pub difficulty: AiDifficulty,
+    pub player_score: u32,
+    pub ai_score: u32,
+    pub longest_rally: u32,
+    pub duration: f32, // Seconds
+    pub date: String,  // YYYY-MM-DD, UTC
+}
+
+#[allow(dead_code)]
+impl Entry {
+    /// The match that just finished, dated today, with no initials yet.
+    pub fn new(score: &Score, stats: &MatchStats, difficulty: AiDifficulty) -> Self {
+        Self {
+            initials: String::new(),
+            difficulty,
+            player_score: score.player_score,
+            ai_score: score.ai_score,
+            longest_rally: stats.longest_rally,
+            duration: stats.duration,
+            date: today(),
+        }
+    }
+
+    pub fn margin(&self) -> i64 {
+        self.player_score as i64 - self.ai_score as i64
+    }
+
+    pub fn value(&self, metric: Metric) -> f64 {
+        match metric {
+            Metric::Margin => self.margin() as f64,
+            Metric::LongestRally => self.longest_rally as f64,
+        }
+    }
+}
+
+/// The best matches on this machine. Every entry records all the metrics,
+/// so the ranking can change without losing anything.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub version: u32,
+    pub entries: Vec<Entry>, // In the order they were added
+}
+
+#[derive(Debug)]
+pub enum LeaderboardError {
+    Io(PathBuf, io::Error),
+    Serialize(PathBuf, serde_json::Error),
+    Version(PathBuf, u32), // Written by a newer build, left untouched
+}
+
+#[allow(dead_code)]
+impl Leaderboard {
+    pub fn new() -> Self {
+        Self {
+            version: VERSION,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Reads a saved leaderboard, or an empty one if the file is missing.
+    /// A file that doesn't parse is moved aside to `<file>.corrupt` and the
+    /// board starts afresh, so the next save doesn't overwrite it. A file
+    /// from a newer build is an error and isn't touched.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LeaderboardError> {
+        let path = path.as_ref();
+        let io_error = |e| LeaderboardError::Io(path.into(), e);
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::new()),
+            Err(e) => return Err(io_error(e)),
+        };
+        let value = serde_json::from_str::<serde_json::Value>(&text);
+        let version = value.as_ref().ok().and_then(|value| value.get("version")?.as_u64());
+        if let Some(version) = version.filter(|&version| version > VERSION as u64) {
+            return Err(LeaderboardError::Version(path.into(), version as u32));
+        }
+        match value.and_then(serde_json::from_value) {
+            Ok(board) => Ok(board),
+            Err(e) => {
+                warn!("leaderboard is damaged, moving it aside"; path = path.display(), error = e);
+                std::fs::rename(path, with_suffix(path, "corrupt")).map_err(io_error)?;
+                Ok(Self::new())
+            }
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), LeaderboardError> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| LeaderboardError::Serialize(path.into(), e))?;
+        write(path, &json)
+    }
+
+    /// Entries ranked best first by `metric`, only those against
+    /// `difficulty` if one is given. Ties keep the older entry first.
+    pub fn ranked(&self, metric: Metric, difficulty: Option<AiDifficulty>) -> Vec<&Entry> {
+        self.ranked_indices(metric, difficulty)
+            .into_iter()
+            .map(|index| &self.entries[index])
+            .collect()
+    }
+
+    fn ranked_indices(&self, metric: Metric, difficulty: Option<AiDifficulty>) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.entries.len())
+            .filter(|&index| difficulty.is_none_or(|d| self.entries[index].difficulty == d))
+            .collect();
+        indices.sort_by(|&a, &b| {
+            let (a, b) = (&self.entries[a], &self.entries[b]);
+            b.value(metric).total_cmp(&a.value(metric))
+        });
+        indices
+    }
+
+    /// The place, from 0, that `entry` would take in the top `size`, or
+    /// `None` if it wouldn't make it.
+    pub fn rank(
+        &self,
+        entry: &Entry,
+        metric: Metric,
+        difficulty: Option<AiDifficulty>,
+        size: usize,
+    ) -> Option<usize> {
+        if difficulty.is_some_and(|d| entry.difficulty != d) {
+            return None;
+        }
+        let value = entry.value(metric);
+        let place = self
+            .ranked(metric, difficulty)
+            .iter()
+            .take_while(|other| other.value(metric) >= value)
+            .count();
+        (place < size).then_some(place)
+    }
+
+    /// Adds an entry, then drops any that no longer make the top `size` of
+    /// any metric, overall or for their difficulty.
+    pub fn insert(&mut self, entry: Entry, size: usize) {
+        self.entries.push(entry);
+
+        let mut keep = vec![false; self.entries.len()];
+        let filters = [None].into_iter().chain(AiDifficulty::ALL.map(Some));
+        for difficulty in filters {
+            for metric in Metric::ALL {
+                for index in self
+                    .ranked_indices(metric, difficulty)
+                    .into_iter()
+                    .take(size)
+                {
+                    keep[index] = true;
+                }
+            }
+        }
+        let mut keep = keep.into_iter();
+        self.entries.retain(|_| keep.next().unwrap_or(true));
+    }
+
+    /// The ranked table as JSON for use outside the game, each entry with
+    /// its rank and value.
+    pub fn export(
+        &self,
+        metric: Metric,
+        difficulty: Option<AiDifficulty>,
+        size: usize,
+    ) -> serde_json::Value {
+        let entries: Vec<serde_json::Value> = self
+            .ranked(metric, difficulty)
+            .into_iter()
+            .take(size)
+            .enumerate()
+            .map(|(index, entry)| {
+                let mut value = serde_json::to_value(entry).unwrap_or_default();
+                value["rank"] = (index + 1).into();
+                value["value"] = entry.value(metric).into();
+                value
+            })
+            .collect();
+        serde_json::json!({
+            "metric": metric,
+            "difficulty": difficulty,
+            "entries": entries,
+        })
+    }
+
+    pub fn save_export(
+        &self,
+        path: impl AsRef<Path>,
+        metric: Metric,
+        difficulty: Option<AiDifficulty>,
+        size: usize,
+    ) -> Result<(), LeaderboardError> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(&self.export(metric, difficulty, size))
+            .map_err(|e| LeaderboardError::Serialize(path.into(), e))?;
+        write(path, &json)
+    }
+}
+
+/// Writes through a temporary file renamed over `path`, so a crash leaves
+/// either the old file or the new one.
+fn write(path: &Path, text: &str) -> Result<(), LeaderboardError> {
+    let io_error = |e| LeaderboardError::Io(path.into(), e);
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    atomic_write(path, text.as_bytes()).map_err(io_error)
+}
+
+impl fmt::Display for LeaderboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LeaderboardError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            LeaderboardError::Serialize(path, e) => {
+                write!(f, "{}: can't write leaderboard: {}", path.display(), e)
+            }
+            LeaderboardError::Version(path, version) => write!(
+                f,
+                "{}: leaderboard version {} is newer than this game supports ({})",
+                path.display(),
+                version,
+                VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LeaderboardError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(initials: &str, difficulty: AiDifficulty, score: (u32, u32), rally: u32) -> Entry {
+        Entry {
+            initials: initials.to_string(),
+            difficulty,
+            player_score: score.0,
+            ai_score: score.1,
+            longest_rally: rally,
+            duration: (score.0 + score.1) as f32 * 10.0,
+            date: "2024-01-01".to_string(),
+        }
+    }
+
+    fn initials(entries: Vec<&Entry>) -> Vec<&str> {
+        entries
+            .iter()
+            .map(|entry| entry.initials.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn ranks_by_metric_and_difficulty() {
+        let mut board = Leaderboard::new();
+        board.insert(entry("AAA", AiDifficulty::Hard, (10, 6), 4), 10);
+        board.insert(entry("BBB", AiDifficulty::Easy, (10, 0), 2), 10);
+        board.insert(entry("CCC", AiDifficulty::Hard, (3, 10), 30), 10);
+        board.insert(entry("DDD", AiDifficulty::Hard, (10, 6), 5), 10);
+
+        assert_eq!(
+            initials(board.ranked(Metric::Margin, None)),
+            ["BBB", "AAA", "DDD", "CCC"]
+        );
+        let hard = board.ranked(Metric::Margin, Some(AiDifficulty::Hard));
+        assert_eq!(initials(hard), ["AAA", "DDD", "CCC"]);
+        assert_eq!(initials(board.ranked(Metric::LongestRally, None))[0], "CCC");
+
+        // Ties go below the entries already there
+        let tie = entry("EEE", AiDifficulty::Hard, (10, 6), 0);
+        assert_eq!(board.rank(&tie, Metric::Margin, None, 10), Some(3));
+        assert_eq!(board.rank(&tie, Metric::Margin, None, 3), None);
+        let easy = entry("FFF", AiDifficulty::Easy, (10, 0), 0);
+        assert_eq!(
+            board.rank(&easy, Metric::Margin, Some(AiDifficulty::Hard), 10),
+            None
+        );
+    }
+
+    #[test]
+    fn keeps_entries_that_rank_somewhere() {
+        let mut board = Leaderboard::new();
+        board.insert(entry("AAA", AiDifficulty::Normal, (10, 1), 2), 1);
+        // Better margin, but AAA keeps the longest rally
+        board.insert(entry("BBB", AiDifficulty::Normal, (10, 0), 1), 1);
+        assert_eq!(board.entries.len(), 2);
+        // Beats both on every metric
+        board.insert(entry("CCC", AiDifficulty::Normal, (12, 0), 9), 1);
+        assert_eq!(initials(board.entries.iter().collect()), ["CCC"]);
+    }
+
+    #[test]
+    fn saves_and_exports() {
+        let dir = std::env::temp_dir().join(format!("pong_leaderboard_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("leaderboard.json");
+        assert_eq!(Leaderboard::load(&path).unwrap(), Leaderboard::new());
+
+        let mut board = Leaderboard::new();
+        board.insert(entry("AAA", AiDifficulty::Hard, (10, 6), 4), 10);
+        board.insert(entry("BBB", AiDifficulty::Hard, (10, 2), 3), 10);
+        board.save(&path).unwrap();
+        assert_eq!(Leaderboard::load(&path).unwrap(), board);
+
+        let export = board.export(Metric::Margin, Some(AiDifficulty::Hard), 10);
+        assert_eq!(export["metric"], "margin");
+        assert_eq!(export["difficulty"], "hard");
+        assert_eq!(export["entries"][0]["initials"], "BBB");
+        assert_eq!(export["entries"][0]["rank"], 1);
+        assert_eq!(export["entries"][1]["value"], 4.0);
+
+        assert!(!dir.join("leaderboard.json.tmp").exists());
+
+        // A damaged board is kept aside and replaced by an empty one
+        std::fs::write(&path, "{ oops").unwrap();
+        assert_eq!(Leaderboard::load(&path).unwrap(), Leaderboard::new());
+        let corrupt = std::fs::read_to_string(dir.join("leaderboard.json.corrupt")).unwrap();
+        assert_eq!(corrupt, "{ oops");
+
+        // A board from a newer build is refused and left as it was
+        let newer = format!(r#"{{ "version": {}, "entries": [], "extra": true }}"#, VERSION + 1);
+        std::fs::write(&path, &newer).unwrap();
+        assert!(matches!(
+            Leaderboard::load(&path),
+            Err(LeaderboardError::Version(_, version)) if version == VERSION + 1
+        ));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), newer);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
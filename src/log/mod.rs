@@ -0,0 +1,329 @@
+// src/log/mod.rs
+pub mod sink;
+
+use std::cell::RefCell;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::Config;
+use crate::engine::date::civil_from_days;
+use sink::{RotatingFile, Sink, Stderr};
+
+/// Logs a message at `level` with optional `key = value` fields after a
+/// semicolon. Values only need `Display`.
+///
+/// ```ignore
+/// log!(Level::Info, "goal scored"; scorer = "player", score = 3);
+/// ```
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $fmt:literal $(, $arg:expr)* $(; $($key:ident = $value:expr),+)?) => {{
+        let level = $level;
+        if $crate::log::enabled(level) {
+            $crate::log::write(
+                level,
+                module_path!(),
+                format!($fmt $(, $arg)*),
+                vec![$($((stringify!($key), $value.to_string())),+)?],
+            );
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($args:tt)+) => { $crate::log!($crate::log::Level::Error, $($args)+) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($args:tt)+) => { $crate::log!($crate::log::Level::Warn, $($args)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($args:tt)+) => { $crate::log!($crate::log::Level::Info, $($args)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($args:tt)+) => { $crate::log!($crate::log::Level::Debug, $($args)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($args:tt)+) => { $crate::log!($crate::log::Level::Trace, $($args)+) };
+}
+
+/// How much to log, most severe first. A level lets through itself and
+/// everything above it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub const ALL: [Level; 5] = [
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Level::ALL
+            .into_iter()
+            .find(|level| level.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown log level '{}'", s))
+    }
+}
+
+/// One log message and its fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub level: Level,
+    pub target: &'static str, // Module that logged it, without the crate name
+    pub message: String,
+    pub fields: Vec<(&'static str, String)>,
+}
+
+#[allow(dead_code)]
+impl Record {
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// `LEVEL target: message key=value ...`, quoting values with spaces.
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = self.level.name().to_uppercase();
+        write!(f, "{:<5} {}: {}", level, self.target, self.message)?;
+        for (key, value) in &self.fields {
+            if value.is_empty() || value.contains([' ', '"', '=']) {
+                write!(f, " {}={:?}", key, value)?;
+            } else {
+                write!(f, " {}={}", key, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where logs go, taken from `Config` and the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub level: Level,
+    pub stderr: bool,
+    pub file: Option<PathBuf>,
+    pub file_max_bytes: u64,
+    pub file_count: usize,
+}
+
+impl Settings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            level: config.log_level,
+            stderr: config.log_stderr,
+            file: config.log_file.as_ref().map(PathBuf::from),
+            file_max_bytes: config.log_file_max_bytes,
+            file_count: config.log_file_count,
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::from_config(&Config::default())
+    }
+}
+
+struct Logger {
+    settings: Settings,
+    sinks: Vec<Box<dyn Sink>>,
+}
+
+static LOGGER: LazyLock<Mutex<Logger>> = LazyLock::new(|| {
+    Mutex::new(Logger {
+        settings: Settings::default(),
+        sinks: vec![Box::new(Stderr)],
+    })
+});
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+thread_local! {
+    static CAPTURED: RefCell<Option<Vec<Record>>> = const { RefCell::new(None) };
+}
+
+/// Switches to `settings`, reopening sinks only if they changed. A log
+/// file that can't be opened is reported and skipped.
+pub fn configure(settings: Settings) {
+    let mut logger = LOGGER.lock().unwrap_or_else(|e| e.into_inner());
+    MAX_LEVEL.store(settings.level as u8, Ordering::Relaxed);
+    if logger.settings == settings {
+        return;
+    }
+
+    let mut sinks: Vec<Box<dyn Sink>> = Vec::new();
+    if settings.stderr {
+        sinks.push(Box::new(Stderr));
+    }
+    let mut file_error = None;
+    if let Some(path) = &settings.file {
+        match RotatingFile::open(path, settings.file_max_bytes, settings.file_count) {
+            Ok(file) => sinks.push(Box::new(file)),
+            Err(e) => file_error = Some(format!("{}: {}", path.display(), e)),
+        }
+    }
+    logger.sinks = sinks;
+    logger.settings = settings;
+    drop(logger);
+
+    if let Some(e) = file_error {
+        crate::warn!("can't open log file, not logging to it"; error = e);
+    }
+}
+
+/// Whether a message at `level` would go anywhere. The macros check this
+/// before formatting.
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= MAX_LEVEL.load(Ordering::Relaxed)
+        || CAPTURED.with(|captured| captured.borrow().is_some())
+}
+
+/// Sends a record to the sinks, and to the capture if this thread has
+/// one. Use the macros rather than calling this.
+pub fn write(
+    level: Level,
+    module: &'static str,
+    message: String,
+    fields: Vec<(&'static str, String)>,
+) {
+    let record = Record {
+        level,
+        target: module.split_once("::").map_or(module, |(_, path)| path),
+        message,
+        fields,
+    };
+
+    if level as u8 <= MAX_LEVEL.load(Ordering::Relaxed) {
+        let line = format!("{} {}", timestamp(), record);
+        let mut logger = LOGGER.lock().unwrap_or_else(|e| e.into_inner());
+        for sink in logger.sinks.iter_mut() {
+            sink.write_line(&line);
+        }
+    }
+
+    CAPTURED.with(|captured| {
+        if let Some(records) = captured.borrow_mut().as_mut() {
+            records.push(record);
+        }
+    });
+}
+
+/// Runs `f` and returns what it logged on this thread at every level,
+/// whatever the configured level. For tests.
+#[allow(dead_code)]
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, Vec<Record>) {
+    let outer = CAPTURED.with(|captured| captured.borrow_mut().replace(Vec::new()));
+    let result = f();
+    let records = CAPTURED.with(|captured| std::mem::replace(&mut *captured.borrow_mut(), outer));
+    (result, records.unwrap_or_default())
+}
+
+/// The current UTC time as `YYYY-MM-DDTHH:MM:SS.mmmZ`.
+fn timestamp() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let secs = now.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let time = secs % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60,
+        now.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_records_with_fields() {
+        let ((), records) = capture(|| {
+            crate::info!("goal scored"; scorer = "player", score = 2 + 1);
+            crate::trace!("tick {}", 7);
+        });
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].level, Level::Info);
+        assert_eq!(records[0].target, "log::tests");
+        assert_eq!(records[0].field("score"), Some("3"));
+        assert_eq!(records[1].message, "tick 7");
+        assert!(records[1].fields.is_empty());
+
+        // Nothing leaks out of the capture
+        let ((), records) = capture(|| {});
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn formats_records() {
+        let record = Record {
+            level: Level::Warn,
+            target: "engine::game",
+            message: "sprite missing".to_string(),
+            fields: vec![
+                ("name", "ball.png".to_string()),
+                ("error", "not found".to_string()),
+            ],
+        };
+        assert_eq!(
+            record.to_string(),
+            "WARN  engine::game: sprite missing name=ball.png error=\"not found\""
+        );
+    }
+
+    #[test]
+    fn parses_levels() {
+        assert_eq!("DEBUG".parse::<Level>(), Ok(Level::Debug));
+        assert!("loud".parse::<Level>().is_err());
+        assert!(Level::Error < Level::Trace);
+    }
+}
@@ -0,0 +1,133 @@
+// src/log/sink.rs
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// A destination for formatted log lines.
+pub trait Sink: Send {
+    fn write_line(&mut self, line: &str);
+}
+
+/// Standard error. Goes through `eprintln!` so the test harness captures it.
+pub struct Stderr;
+
+impl Sink for Stderr {
+    fn write_line(&mut self, line: &str) {
+        eprintln!("{}", line);
+    }
+}
+
+/// Appends to a file, rotating it once it would pass `max_bytes`: the
+/// file becomes `<name>.1`, `<name>.1` becomes `<name>.2` and so on, with
+/// `keep` old files kept.
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+    keep: usize,
+}
+
+impl RotatingFile {
+    pub fn open(path: impl Into<PathBuf>, max_bytes: u64, keep: usize) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_bytes,
+            keep,
+        })
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        name.into()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.keep == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            for index in (1..self.keep).rev() {
+                let from = self.rotated_path(index);
+                if from.exists() {
+                    std::fs::rename(&from, self.rotated_path(index + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, self.rotated_path(1))?;
+            self.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+        }
+        self.size = 0;
+        Ok(())
+    }
+
+    fn append(&mut self, line: &str) -> io::Result<()> {
+        let length = line.len() as u64 + 1;
+        if self.size > 0 && self.size + length > self.max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += length;
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Sink for RotatingFile {
+    fn write_line(&mut self, line: &str) {
+        // Logging about a broken log file could recurse, so say it plainly
+        if let Err(e) = self.append(line) {
+            eprintln!("Failed to write log {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_files() {
+        let dir = std::env::temp_dir().join(format!("pong_log_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("game.log");
+
+        // Two 10-byte lines fit in 25 bytes, the third starts a new file
+        let mut sink = RotatingFile::open(&path, 25, 2).unwrap();
+        for line in [
+            "line 0001",
+            "line 0002",
+            "line 0003",
+            "line 0004",
+            "line 0005",
+        ] {
+            sink.write_line(line);
+        }
+
+        let read = |path: &Path| std::fs::read_to_string(path).unwrap();
+        assert_eq!(read(&path), "line 0005\n");
+        assert_eq!(read(&sink.rotated_path(1)), "line 0003\nline 0004\n");
+        assert_eq!(read(&sink.rotated_path(2)), "line 0001\nline 0002\n");
+        assert!(!sink.rotated_path(3).exists());
+
+        // Reopening appends and carries on counting from the file's size
+        let mut sink = RotatingFile::open(&path, 25, 2).unwrap();
+        sink.write_line("line 0006");
+        assert_eq!(read(&path), "line 0005\nline 0006\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
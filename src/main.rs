@@ -1,59 +1,641 @@
 // src/main.rs
+mod achievements;
+mod assets;
+mod audio;
+mod cli;
 mod components;
+mod console;
+mod ecs;
 mod engine;
+mod leaderboard;
+mod log;
+mod profile;
 mod systems;
+mod ui;
 
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use engine::error::EngineError;
+use engine::overlay::DebugOverlay;
+use engine::profiler::Profiler;
+use engine::watcher::ConfigWatcher;
 use engine::{Config, Engine};
+use minifb::Key;
 
+use crate::achievements::{Achievement, Toasts};
+use crate::audio::{Audio, NullBackend};
 use crate::components::Paddle;
-use crate::systems::ScoringSystem;
-use components::{Ball, Score};
-use systems::{CollisionSystem, MovementSystem};
+use crate::console::Console;
+use crate::ecs::{Events, Schedule, World};
+use crate::leaderboard::{Entry, GameOver, Leaderboard};
+use crate::profile::{Profile, ProfileStore};
+use crate::systems::{AchievementSystem, AudioSystem, GameEvent, ScoringSystem, StatsSystem};
+use crate::ui::{Input, MainMenu, MenuAction, OptionsScreen, PauseMenu, QuitDialog, StatsScreen};
+use components::{
+    Ball, DebugInfo, ExtraBall, GameMode, MatchStats, Particles, Rally, Score, ScreenShake, Trail,
+};
+use systems::{CollisionSystem, DebugSystem, EffectsSystem, MovementSystem, MultiballSystem};
+
+fn main() -> ExitCode {
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    if options.help {
+        println!("{}", cli::USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let result = load_config(&options).and_then(|config| match &options.record_match {
+        Some(path) => record_match(config, path, options.max_frames),
+        None => run(config, &options),
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            error!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// What the window shows: a menu over the frozen court, or the match.
+enum Screen {
+    Menu,
+    Options {
+        screen: Box<OptionsScreen>,
+        in_match: bool, // Opened from the pause menu, so leaving goes back there
+    },
+    Stats,
+    Playing,
+    Paused,
+}
 
-fn main() {
-    let config = Config::default();
-    let mut engine = Engine::new(config);
+/// Plays the game in a window until it's closed.
+fn run(config: Config, options: &cli::Options) -> Result<(), EngineError> {
+    let mut engine = Engine::new(config)?;
+    let mut watcher = ConfigWatcher::new(&options.config);
 
     // Initialize game objects
-    let mut ball = Ball::new(400.0, 300.0);
-    let mut player_paddle = Paddle::new(50.0, 250.0, true);
-    let mut ai_paddle = Paddle::new(750.0, 250.0, false);
-    let mut score = Score::new();
+    let mut world = create_world(&engine.config, true);
 
-    // Game systems
-    let movement_system = MovementSystem::new();
-    let collision_system = CollisionSystem::new();
-    let mut scoring_system = ScoringSystem::new();
+    // Game systems, run in stage order
+    let achievements = load_achievements(&engine.config);
+    let mut schedule = create_schedule(Audio::new(audio::default_backend()), achievements);
+    let mut overlay = DebugOverlay::new(engine.config.debug_overlay);
+    let mut console = Console::new();
+    let mut show_stats = false;
+    let mut game_over: Option<GameOver> = None;
+    let mut screen = Screen::Menu;
+    let mut main_menu = MainMenu::new();
+    let mut stats_screen = StatsScreen::new();
+    let mut pause_menu = PauseMenu::new();
+    let mut quit_dialog: Option<QuitDialog> = None;
+    engine.set_paused(true);
 
-    // Welcome message
-    println!("Welcome to Pong!");
+    // Lifetime stats for the player, the game still runs without them
+    let profiles = ProfileStore::new(&engine.config.profile_dir);
+    match profiles.open(&engine.config.profile_name) {
+        Ok(profile) => {
+            info!("loaded profile"; name = profile.name, matches = profile.stats.matches_played);
+            world.insert_resource(profile);
+        }
+        Err(e) => error!("stats won't be recorded"; error = e),
+    }
+    match Leaderboard::load(&engine.config.leaderboard_file) {
+        Ok(board) => world.insert_resource(board),
+        Err(e) => error!("leaderboard unavailable"; error = e),
+    }
+
+    info!("welcome to Pong!"; difficulty = engine.config.ai_difficulty.name());
 
     // Main game loop
     while engine.is_running() {
+        let frame_start = Instant::now();
+        reload_config(&mut engine, &mut watcher, options);
+
+        // Screens take the keys before the simulation runs, so a pause
+        // freezes this frame. Keys one screen acts on aren't passed on to the
+        // menus, since they're still down when those are drawn.
+        let mut input = if console.is_open() {
+            Input::default()
+        } else {
+            Input::read(&engine)
+        };
+        if !console.is_open()
+            && quit_dialog.is_none()
+            && take_keys(&mut engine, &mut world, &mut screen, &mut game_over, &mut pause_menu)
+        {
+            input = Input::default();
+        }
         let delta_time = engine.get_delta_time();
+        overlay.record_frame(engine.frame_time());
 
         // Handle input
-        engine.handle_input(&mut player_paddle);
-
-        // Update systems
-        // movement_system.update(&mut ball, &mut player_paddle, &mut ai_paddle, delta_time);
-        movement_system.update(
-            &mut ball,
-            &mut player_paddle,
-            &mut ai_paddle,
-            delta_time,
-            &engine.config,
+        profile_scope!(&world, "input", {
+            let mode = GameMode::of(&world);
+            for (_, paddle) in world.components_mut::<Paddle>().iter_mut() {
+                engine.handle_input(paddle, mode);
+            }
+        });
+
+        // Update systems, events only live for the frame they were sent in.
+        // While paused the frozen frame is still drawn.
+        if delta_time > 0.0 {
+            world.resource_mut::<Events<GameEvent>>().clear();
+            schedule.run(&mut world, &engine.config, delta_time);
+            let match_won = world
+                .resource::<Events<GameEvent>>()
+                .iter()
+                .find_map(|event| match event {
+                    GameEvent::MatchWon { player_won } => Some(*player_won),
+                    _ => None,
+                });
+            if let Some(player_won) = match_won {
+                save_profile(&profiles, &world);
+                let entry = Entry::new(
+                    &world.resource::<Score>(),
+                    &world.resource::<MatchStats>(),
+                    engine.config.ai_difficulty,
+                );
+                game_over = Some(GameOver::new(entry, player_won, &world, &engine.config));
+                engine.set_paused(true);
+            }
+        }
+
+        let menu_action = profile_scope!(&world, "render", {
+            render(&mut engine, &world);
+            if overlay.enabled {
+                engine.render_debug_overlay(
+                    &overlay,
+                    &world.resource::<DebugInfo>(),
+                    &world.components::<Ball>(),
+                    &world.components::<Paddle>(),
+                );
+                engine.render_profiler(&overlay, &world.resource::<Profiler>());
+            }
+            if let Some(screen) = &game_over {
+                screen.draw(engine.framebuffer_mut(), &world);
+            }
+            // The quit dialog takes the input from the screen under it
+            let (screen_input, dialog_input) = match quit_dialog {
+                Some(_) => (Input::default(), input),
+                None => (input, Input::default()),
+            };
+            let frame = engine.framebuffer_mut();
+            let mut action = match &mut screen {
+                Screen::Menu => main_menu.show(frame, screen_input),
+                Screen::Options { screen, .. } => screen.show(frame, screen_input),
+                Screen::Stats => {
+                    let profile = world
+                        .has_resource::<Profile>()
+                        .then(|| world.resource::<Profile>());
+                    stats_screen.show(frame, screen_input, profile.as_deref())
+                }
+                Screen::Paused => pause_menu.show(frame, screen_input),
+                Screen::Playing => {
+                    if show_stats && world.has_resource::<Profile>() {
+                        profile::screen::draw(frame, &world.resource::<Profile>());
+                    }
+                    None
+                }
+            };
+            if let Some(dialog) = &mut quit_dialog {
+                action = dialog.show(frame, dialog_input).or(action);
+            }
+            let mut toasts = world.resource_mut::<Toasts>();
+            toasts.update(engine.frame_time());
+            toasts.draw(engine.framebuffer_mut());
+            console.draw(engine.framebuffer_mut());
+            action
+        });
+        // Includes the wait for the window's frame rate limit
+        profile_scope!(&world, "present", {
+            engine.present()
+        })?;
+
+        if engine.key_pressed(Key::Backquote) {
+            console.toggle();
+            engine.set_input_captured(console.is_open());
+            debug!("console toggled"; open = console.is_open());
+        }
+        console.update(&mut engine, &mut world);
+        match menu_action {
+            Some(MenuAction::Quit) => quit_dialog = Some(QuitDialog::new()),
+            Some(MenuAction::ConfirmQuit) => engine.quit(),
+            Some(_) if quit_dialog.is_some() => quit_dialog = None,
+            Some(action) => {
+                let current = std::mem::replace(&mut screen, Screen::Menu);
+                screen = follow_menu(
+                    action,
+                    current,
+                    &mut engine,
+                    &mut world,
+                    &mut game_over,
+                    &options.config,
+                );
+            }
+            None => {}
+        }
+        // Menus keep the keyboard from the paddles
+        if !matches!(screen, Screen::Playing) {
+            engine.set_input_captured(true);
+        }
+        if engine.key_pressed(Key::F1) {
+            show_stats = !show_stats;
+        }
+        if engine.key_pressed(Key::F3) {
+            overlay.toggle();
+            debug!("debug overlay toggled"; enabled = overlay.enabled);
+        }
+        if engine.key_pressed(Key::F12) {
+            match engine.save_screenshot(&engine.config.screenshot_dir) {
+                Ok(path) => info!("saved screenshot"; path = path.display()),
+                Err(e) => error!("failed to save screenshot"; error = e),
+            }
+        }
+        if engine.key_pressed(Key::F9) {
+            engine.toggle_recording();
+        }
+        if engine.key_pressed(Key::F10) {
+            engine.config.scale_mode = engine.config.scale_mode.next();
+            info!("scale mode changed"; mode = format!("{:?}", engine.config.scale_mode));
+        }
+        if engine.key_pressed(Key::F11) {
+            engine.toggle_fullscreen();
+        }
+        world.resource_mut::<Profiler>().end_frame(frame_start.elapsed());
+    }
+
+    if let Some(Err(e)) = engine.stop_recording() {
+        error!("failed to save recording"; error = e);
+    }
+    save_profile(&profiles, &world);
+    info!("game closed");
+    Ok(())
+}
+
+/// Reads the config file, or uses the defaults if there isn't one, then
+/// applies the command line and sets up logging.
+fn load_config(options: &cli::Options) -> Result<Config, EngineError> {
+    let path = &options.config;
+    let mut config = if path.exists() {
+        Config::load(path)?
+    } else {
+        Config::default()
+    };
+    options.apply(&mut config);
+    log::configure(log::Settings::from_config(&config));
+
+    if path.exists() {
+        info!("loaded config"; path = path.display());
+    } else {
+        info!("no config file, using defaults"; path = path.display());
+    }
+    Ok(config)
+}
+
+/// Applies the config file if it changed. A file that doesn't load leaves
+/// the running config alone.
+fn reload_config(engine: &mut Engine, watcher: &mut ConfigWatcher, options: &cli::Options) {
+    match watcher.poll() {
+        Some(Ok(mut config)) => {
+            options.apply(&mut config);
+            log::configure(log::Settings::from_config(&config));
+            info!("reloaded config"; path = watcher.path().display());
+            match engine.reload_config(config) {
+                Ok(pending) if !pending.is_empty() => {
+                    warn!("restart to apply config changes"; fields = pending.join(","));
+                }
+                Ok(_) => {}
+                Err(e) => error!("config not reloaded, keeping the current one"; error = e),
+            }
+        }
+        Some(Err(e)) => error!("config not reloaded, keeping the current one"; error = e),
+        None => {}
+    }
+}
+
+/// Reads the achievement definitions, falling back to the built-in ones if
+/// the file doesn't load.
+fn load_achievements(config: &Config) -> Vec<Achievement> {
+    let Some(path) = &config.achievements_file else {
+        return achievements::defaults();
+    };
+    match achievements::load(path) {
+        Ok(list) => {
+            info!("loaded achievements"; path = path, count = list.len());
+            list
+        }
+        Err(e) => {
+            error!("using the built-in achievements"; error = e);
+            achievements::defaults()
+        }
+    }
+}
+
+/// Lets the match, its game over screen or the pause menu act on this
+/// frame's keys. Returns true if they were used.
+fn take_keys(
+    engine: &mut Engine,
+    world: &mut World,
+    screen: &mut Screen,
+    game_over: &mut Option<GameOver>,
+    pause_menu: &mut PauseMenu,
+) -> bool {
+    let escape = engine.key_pressed(Key::Escape);
+    let pause_pressed = escape || engine.key_pressed(Key::P) || engine.key_pressed(Key::Space);
+    let playing = matches!(screen, Screen::Playing);
+    match game_over {
+        // Once the initials are in, Escape leaves through the pause menu
+        Some(over) if playing && escape && !over.is_entering() => {
+            *screen = pause(engine, pause_menu);
+        }
+        Some(over) if playing => {
+            if over.update(engine, world) {
+                *game_over = None;
+                new_match(world, &engine.config);
+                engine.set_paused(false);
+            }
+        }
+        None if playing && (pause_pressed || !engine.is_focused()) => {
+            *screen = pause(engine, pause_menu);
+        }
+        _ if matches!(screen, Screen::Paused) && engine.key_pressed(Key::P) => {
+            *screen = resume(engine, game_over.is_some());
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Freezes the match under the pause menu.
+fn pause(engine: &mut Engine, pause_menu: &mut PauseMenu) -> Screen {
+    pause_menu.reset();
+    engine.set_paused(true);
+    engine.set_input_captured(true);
+    info!("paused"; focused = engine.is_focused());
+    Screen::Paused
+}
+
+/// Back to the match. A finished one stays frozen under its game over
+/// screen.
+fn resume(engine: &mut Engine, finished: bool) -> Screen {
+    engine.set_paused(finished);
+    engine.set_input_captured(false);
+    Screen::Playing
+}
+
+/// Does what a menu asked and returns the screen to show next.
+fn follow_menu(
+    action: MenuAction,
+    current: Screen,
+    engine: &mut Engine,
+    world: &mut World,
+    game_over: &mut Option<GameOver>,
+    config_path: &Path,
+) -> Screen {
+    match (action, current) {
+        (MenuAction::Play | MenuAction::TwoPlayers, _) => {
+            let mode = if action == MenuAction::TwoPlayers {
+                GameMode::Versus
+            } else {
+                GameMode::Single
+            };
+            set_mode(world, &engine.config, mode);
+            *game_over = None;
+            new_match(world, &engine.config);
+            resume(engine, false)
+        }
+        (MenuAction::Restart, _) => {
+            *game_over = None;
+            new_match(world, &engine.config);
+            resume(engine, false)
+        }
+        (MenuAction::Resume, _) => resume(engine, game_over.is_some()),
+        (MenuAction::Options, current) => Screen::Options {
+            screen: Box::new(OptionsScreen::new(&engine.config)),
+            in_match: matches!(current, Screen::Paused),
+        },
+        (MenuAction::Stats, _) => Screen::Stats,
+        (MenuAction::SaveOptions | MenuAction::Back, Screen::Options { screen, in_match }) => {
+            if action == MenuAction::SaveOptions {
+                apply_options(engine, &screen, config_path);
+            }
+            if in_match { Screen::Paused } else { Screen::Menu }
+        }
+        (MenuAction::QuitToMenu, _) => {
+            *game_over = None;
+            Screen::Menu
+        }
+        (MenuAction::SaveOptions | MenuAction::Back, _) => Screen::Menu,
+        // Answered by the quit dialog
+        (MenuAction::Quit | MenuAction::ConfirmQuit, current) => current,
+    }
+}
+
+/// Applies the options screen's settings to the running game and writes
+/// them to the config file, keeping the file's other settings.
+fn apply_options(engine: &mut Engine, screen: &OptionsScreen, path: &Path) {
+    let mut config = engine.config.clone();
+    screen.apply_to(&mut config);
+    if let Err(e) = engine.apply_config(config) {
+        error!("options not applied"; error = e);
+        return;
+    }
+
+    let saved = if path.exists() {
+        Config::load(path)
+    } else {
+        Ok(Config::default())
+    };
+    let result = saved.and_then(|mut saved| {
+        screen.apply_to(&mut saved);
+        saved.save(path)
+    });
+    match result {
+        Ok(()) => info!("saved options"; path = path.display()),
+        Err(e) => error!("failed to save options"; error = e),
+    }
+}
+
+/// Saves the player's profile, if one was loaded.
+fn save_profile(profiles: &ProfileStore, world: &World) {
+    if !world.has_resource::<Profile>() {
+        return;
+    }
+    let profile = world.resource::<Profile>();
+    match profiles.save(&profile) {
+        Ok(()) => debug!("saved profile"; name = profile.name),
+        Err(e) => error!("failed to save profile"; error = e),
+    }
+}
+
+/// Clears the score and serves again, dropping any extra balls.
+fn new_match(world: &mut World, config: &Config) {
+    world.resource_mut::<Score>().reset();
+    world.resource_mut::<Rally>().reset();
+    world.resource_mut::<MatchStats>().reset();
+    let extras: Vec<_> = world
+        .components::<ExtraBall>()
+        .iter()
+        .map(|(entity, _)| entity)
+        .collect();
+    for entity in extras {
+        world.despawn(entity);
+    }
+    let center_x = config.window_width as f32 / 2.0;
+    let center_y = config.window_height as f32 / 2.0;
+    for (_, ball) in world.components_mut::<Ball>().iter_mut() {
+        ball.reset(center_x, center_y, config.ball_speed);
+    }
+    info!("new match");
+}
+
+/// Hands the right paddle to a second player in a versus match, or back to
+/// the AI.
+fn set_mode(world: &mut World, config: &Config, mode: GameMode) {
+    let center_x = config.window_width as f32 / 2.0;
+    for (_, paddle) in world.components_mut::<Paddle>().iter_mut() {
+        paddle.is_second_player = mode == GameMode::Versus && paddle.x > center_x;
+    }
+    world.insert_resource(mode);
+    info!("game mode set"; mode = format!("{:?}", mode));
+}
+
+/// Spawns the ball and both paddles. Without a player both paddles are AI.
+fn create_world(config: &Config, with_player: bool) -> World {
+    let mut world = World::new();
+    world.register::<Ball>();
+    world.register::<Paddle>();
+    world.register::<ExtraBall>();
+    world.register::<Trail>();
+    world.spawn_with(Ball::new(400.0, 300.0, config.ball_speed));
+    world.spawn_with(Paddle::new(50.0, 250.0, with_player));
+    world.spawn_with(Paddle::new(750.0, 250.0, false));
+    world.insert_resource(GameMode::Single);
+    world.insert_resource(Score::new());
+    world.insert_resource(Rally::new());
+    world.insert_resource(MatchStats::new());
+    world.insert_resource(Toasts::new());
+    world.insert_resource(Events::<GameEvent>::new());
+    world.insert_resource(Particles::default());
+    world.insert_resource(ScreenShake::default());
+    world.insert_resource(DebugInfo::default());
+    world.insert_resource(Profiler::new());
+    world
+}
+
+fn create_schedule(audio: Audio, achievements: Vec<Achievement>) -> Schedule {
+    let mut schedule = Schedule::new();
+    schedule
+        .add_system(DebugSystem::new())
+        .add_system(MovementSystem::new())
+        .add_system(CollisionSystem::new())
+        .add_system(ScoringSystem::new())
+        .add_system(MultiballSystem::new())
+        .add_system(StatsSystem::new())
+        .add_system(AchievementSystem::new(achievements))
+        .add_system(AudioSystem::new(audio))
+        .add_system(EffectsSystem::new());
+    schedule
+}
+
+fn render(engine: &mut Engine, world: &World) {
+    engine.clear();
+    for (_, trail) in world.components::<Trail>().iter() {
+        engine.render_trail(trail);
+    }
+    for (_, paddle) in world.components::<Paddle>().iter() {
+        engine.render_paddle(paddle);
+    }
+    for (_, ball) in world.components::<Ball>().iter() {
+        engine.render_ball(ball);
+    }
+    engine.render_particles(&world.resource::<Particles>());
+    engine.render_score(&world.resource::<Score>());
+    engine.apply_screen_shake(&world.resource::<ScreenShake>());
+}
+
+/// Plays AI against AI without a window at a fixed time step, recording
+/// every frame until the match is won or `max_frames` have passed.
+fn record_match(config: Config, path: &Path, max_frames: u64) -> Result<(), EngineError> {
+    let mut engine = Engine::headless(config)?;
+    let mut world = create_world(&engine.config, false);
+    let mut schedule = create_schedule(Audio::new(Box::new(NullBackend::new())), Vec::new());
+    let delta_time = 1.0 / engine.config.fps_target as f32;
+
+    engine.start_recording(path)?;
+
+    let mut frames = 0;
+    let mut finished = false;
+    while frames < max_frames && !finished {
+        world.resource_mut::<Events<GameEvent>>().clear();
+        schedule.run(&mut world, &engine.config, delta_time);
+        render(&mut engine, &world);
+        engine.present()?;
+        frames += 1;
+
+        finished = world
+            .resource::<Events<GameEvent>>()
+            .iter()
+            .any(|event| matches!(event, GameEvent::MatchWon { .. }));
+    }
+
+    let score = world.resource::<Score>();
+    if let Some(result) = engine.stop_recording() {
+        println!(
+            "Recorded {} frames ({} - {}) to {}",
+            frames,
+            score.player_score,
+            score.ai_score,
+            result?.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::display::HeadlessDisplay;
+
+    #[test]
+    fn game_over_leaves_through_the_pause_menu() {
+        let mut display = HeadlessDisplay::new(800, 600);
+        display.press_key(Key::Escape);
+        let config = Config::default();
+        let mut engine = Engine::with_display(config.clone(), Box::new(display)).unwrap();
+        let mut world = create_world(&config, true);
+        set_mode(&mut world, &config, GameMode::Versus);
+        let entry = Entry::new(
+            &world.resource::<Score>(),
+            &world.resource::<MatchStats>(),
+            config.ai_difficulty,
         );
+        let mut game_over = Some(GameOver::new(entry, true, &world, &config));
+        let mut screen = Screen::Playing;
+        let mut pause_menu = PauseMenu::new();
+        engine.set_paused(true);
+        let path = Path::new("unused.json");
 
-        collision_system.update(&mut ball, &player_paddle, &ai_paddle, &engine.config);
-        scoring_system.update(&mut ball, &mut score, &engine.config);
+        // Escape on the result opens the pause menu, resuming goes back to it
+        assert!(take_keys(&mut engine, &mut world, &mut screen, &mut game_over, &mut pause_menu));
+        assert!(matches!(screen, Screen::Paused));
+        let action = MenuAction::Resume;
+        screen = follow_menu(action, screen, &mut engine, &mut world, &mut game_over, path);
+        assert!(matches!(screen, Screen::Playing));
+        assert!(game_over.is_some() && engine.is_paused());
 
-        // Render
-        engine.clear();
-        engine.render_paddle(&player_paddle);
-        engine.render_paddle(&ai_paddle);
-        engine.render_ball(&ball);
-        engine.render_score(&score);
-        engine.present();
+        assert!(take_keys(&mut engine, &mut world, &mut screen, &mut game_over, &mut pause_menu));
+        let action = MenuAction::QuitToMenu;
+        screen = follow_menu(action, screen, &mut engine, &mut world, &mut game_over, path);
+        assert!(matches!(screen, Screen::Menu));
+        assert!(game_over.is_none());
     }
 }
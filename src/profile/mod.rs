@@ -0,0 +1,125 @@
+// src/profile/mod.rs
+pub mod screen;
+pub mod store;
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::config::AiDifficulty;
+
+pub use store::ProfileStore;
+
+/// Version written to profile files. Bump it when a field changes meaning,
+/// and teach `ProfileStore` to migrate the older files.
+pub const VERSION: u32 = 1;
+
+/// Matches played and won against one AI difficulty.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Record {
+    pub played: u32,
+    pub won: u32,
+}
+
+/// Lifetime statistics for the human player. Fields missing from a file
+/// start at zero, so adding one doesn't need a new version.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub matches_played: u32,
+    pub matches_won: u32,
+    pub points_won: u32,
+    pub points_lost: u32,
+    pub longest_rally: u32,  // Paddle hits between two points
+    pub fastest_return: f32, // Ball speed off the player's paddle, pixels per second
+    pub time_played: f64,    // Seconds of unpaused play
+    pub difficulties: BTreeMap<AiDifficulty, Record>,
+}
+
+#[allow(dead_code)]
+impl Stats {
+    pub fn record_point(&mut self, player_scored: bool, rally: u32) {
+        if player_scored {
+            self.points_won += 1;
+        } else {
+            self.points_lost += 1;
+        }
+        self.longest_rally = self.longest_rally.max(rally);
+    }
+
+    pub fn record_return(&mut self, speed: f32) {
+        self.fastest_return = self.fastest_return.max(speed);
+    }
+
+    pub fn record_match(&mut self, player_won: bool, difficulty: AiDifficulty) {
+        let record = self.difficulties.entry(difficulty).or_default();
+        record.played += 1;
+        self.matches_played += 1;
+        if player_won {
+            record.won += 1;
+            self.matches_won += 1;
+        }
+    }
+
+    /// Share of matches won, from 0 to 1.
+    pub fn win_rate(&self) -> f32 {
+        if self.matches_played == 0 {
+            return 0.0;
+        }
+        self.matches_won as f32 / self.matches_played as f32
+    }
+}
+
+/// A named player and their stats, as saved by `ProfileStore`. The active
+/// profile lives in the `World` as a resource.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub stats: Stats,
+    #[serde(default)]
+    pub achievements: BTreeMap<String, String>, // Achievement id to the date it was unlocked
+}
+
+impl Profile {
+    pub fn new(name: &str) -> Self {
+        Self {
+            version: VERSION,
+            name: name.to_string(),
+            stats: Stats::default(),
+            achievements: BTreeMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_matches_per_difficulty() {
+        let mut stats = Stats::default();
+        stats.record_match(true, AiDifficulty::Easy);
+        stats.record_match(false, AiDifficulty::Hard);
+        stats.record_match(true, AiDifficulty::Hard);
+        stats.record_point(true, 4);
+        stats.record_point(false, 2);
+
+        assert_eq!((stats.matches_played, stats.matches_won), (3, 2));
+        assert_eq!(
+            stats.difficulties[&AiDifficulty::Hard],
+            Record { played: 2, won: 1 }
+        );
+        assert!(!stats.difficulties.contains_key(&AiDifficulty::Normal));
+        assert_eq!(
+            (stats.points_won, stats.points_lost, stats.longest_rally),
+            (1, 1, 4)
+        );
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.contains(r#""difficulties":{"easy":"#));
+        assert_eq!(serde_json::from_str::<Stats>(&json).unwrap(), stats);
+    }
+}
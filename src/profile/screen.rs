@@ -0,0 +1,81 @@
+// src/profile/screen.rs
+use crate::engine::config::AiDifficulty;
+use crate::engine::font::{LINE_HEIGHT, text_width};
+use crate::engine::framebuffer::{Framebuffer, opaque, with_alpha};
+use crate::profile::Profile;
+
+const MARGIN: i32 = 16;
+const TITLE_SCALE: usize = 2;
+const TEXT_SCALE: usize = 2;
+
+const TITLE: u32 = opaque(0xFFFF80);
+const TEXT: u32 = opaque(0xFFFFFF);
+const EDGE: u32 = opaque(0x606080);
+
+/// The profile's stats as text, one line each. Shared by the stats screen
+/// and the console's `stats` command.
+pub fn lines(profile: &Profile) -> Vec<String> {
+    let stats = &profile.stats;
+    let minutes = (stats.time_played / 60.0) as u64;
+    let mut lines = vec![
+        format!(
+            "MATCHES {}  WON {}  ({:.0}%)",
+            stats.matches_played,
+            stats.matches_won,
+            stats.win_rate() * 100.0
+        ),
+        format!("POINTS {} - {}", stats.points_won, stats.points_lost),
+        format!("LONGEST RALLY {} HITS", stats.longest_rally),
+        format!("FASTEST RETURN {:.0} PX/S", stats.fastest_return),
+        format!("TIME PLAYED {}H {:02}M", minutes / 60, minutes % 60),
+        format!("ACHIEVEMENTS {}", profile.achievements.len()),
+    ];
+    for difficulty in AiDifficulty::ALL {
+        let record = stats
+            .difficulties
+            .get(&difficulty)
+            .copied()
+            .unwrap_or_default();
+        lines.push(format!(
+            "VS {:<6} {} - {}",
+            difficulty.name().to_uppercase(),
+            record.won,
+            record.played.saturating_sub(record.won)
+        ));
+    }
+    lines
+}
+
+/// Draws the stats screen centred over the frame.
+pub fn draw(frame: &mut Framebuffer, profile: &Profile) {
+    let title = profile.name.to_uppercase();
+    let lines = lines(profile);
+    let line_height = (LINE_HEIGHT * TEXT_SCALE) as i32;
+    let title_height = (LINE_HEIGHT * TITLE_SCALE) as i32;
+
+    let widest = lines
+        .iter()
+        .map(|line| text_width(line, TEXT_SCALE))
+        .chain([text_width(&title, TITLE_SCALE)])
+        .max()
+        .unwrap_or(0) as i32;
+    let width = widest + MARGIN * 2;
+    let height = title_height + lines.len() as i32 * line_height + MARGIN * 3;
+    let left = (frame.width() as i32 - width) / 2;
+    let top = (frame.height() as i32 - height) / 2;
+
+    frame.fill_rect(
+        0,
+        0,
+        frame.width() as i32,
+        frame.height() as i32,
+        with_alpha(0x000000, 0.6),
+    );
+    frame.fill_rect(left, top, width, height, with_alpha(0x101018, 0.9));
+    frame.rect(left, top, width, height, EDGE);
+    frame.text(&title, left + MARGIN, top + MARGIN, TITLE_SCALE, TITLE);
+    for (index, line) in lines.iter().enumerate() {
+        let y = top + MARGIN * 2 + title_height + index as i32 * line_height;
+        frame.text(line, left + MARGIN, y, TEXT_SCALE, TEXT);
+    }
+}
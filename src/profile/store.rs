@@ -0,0 +1,198 @@
+// src/profile/store.rs
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::engine::fs::{atomic_write_with_backup, with_suffix};
+use crate::profile::{Profile, VERSION};
+use crate::{info, warn};
+
+#[derive(Debug)]
+pub enum ProfileError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, serde_json::Error),
+    Serialize(PathBuf, serde_json::Error),
+    Version(PathBuf, u32), // Written by a newer build, left untouched
+}
+
+/// Profiles saved as `<dir>/<name>.json`. Saves go through a temporary
+/// file and keep the previous save as `<name>.json.bak`, so a crash or a
+/// damaged file loses at most one save.
+pub struct ProfileStore {
+    dir: PathBuf,
+}
+
+#[allow(dead_code)]
+impl ProfileStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Where `name` is saved. Characters that aren't safe in a file name
+    /// become underscores.
+    pub fn path(&self, name: &str) -> PathBuf {
+        let stem: String = name
+            .trim()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let stem = if stem.is_empty() { "player" } else { &stem };
+        self.dir.join(format!("{}.json", stem))
+    }
+
+    /// Reads a saved profile, or `None` if there isn't one.
+    pub fn load(&self, name: &str) -> Result<Option<Profile>, ProfileError> {
+        read(&self.path(name))
+    }
+
+    /// Loads `name`, falling back to its backup and then to a new profile.
+    /// A file that doesn't parse is moved aside to `<name>.json.corrupt`
+    /// rather than overwritten. Only unreadable files and files from a
+    /// newer version are errors.
+    pub fn open(&self, name: &str) -> Result<Profile, ProfileError> {
+        let path = self.path(name);
+        match read(&path) {
+            Ok(Some(profile)) => return Ok(profile),
+            Ok(None) => {}
+            Err(ProfileError::Parse(_, e)) => {
+                let corrupt = with_suffix(&path, "corrupt");
+                warn!("profile is damaged, moving it aside"; path = path.display(), error = e);
+                std::fs::rename(&path, &corrupt).map_err(|e| ProfileError::Io(path.clone(), e))?;
+            }
+            Err(e) => return Err(e),
+        }
+
+        let backup = with_suffix(&path, "bak");
+        match read(&backup) {
+            Ok(Some(profile)) => {
+                info!("restored profile from backup"; path = backup.display());
+                Ok(profile)
+            }
+            Ok(None) => Ok(Profile::new(name)),
+            Err(ProfileError::Parse(_, e)) => {
+                warn!("profile backup is damaged, starting afresh"; path = backup.display(), error = e);
+                Ok(Profile::new(name))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, profile: &Profile) -> Result<(), ProfileError> {
+        let path = self.path(&profile.name);
+        let io_error = |e| ProfileError::Io(path.clone(), e);
+        std::fs::create_dir_all(&self.dir).map_err(io_error)?;
+
+        let json = serde_json::to_string_pretty(profile)
+            .map_err(|e| ProfileError::Serialize(path.clone(), e))?;
+        atomic_write_with_backup(&path, json.as_bytes(), &with_suffix(&path, "bak"))
+            .map_err(io_error)
+    }
+}
+
+/// Reads a profile file, checking its version before the rest so a newer
+/// file isn't mistaken for a damaged one.
+fn read(path: &Path) -> Result<Option<Profile>, ProfileError> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(ProfileError::Io(path.into(), e)),
+    };
+    let parse_error = |e| ProfileError::Parse(path.into(), e);
+    let value: serde_json::Value = serde_json::from_str(&text).map_err(parse_error)?;
+    let version = value.get("version").and_then(|version| version.as_u64());
+    if let Some(version) = version.filter(|&version| version > VERSION as u64) {
+        return Err(ProfileError::Version(path.into(), version as u32));
+    }
+    serde_json::from_value(value).map(Some).map_err(parse_error)
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            ProfileError::Parse(path, e) => write!(f, "{}: invalid profile: {}", path.display(), e),
+            ProfileError::Serialize(path, e) => {
+                write!(f, "{}: can't write profile: {}", path.display(), e)
+            }
+            ProfileError::Version(path, version) => write!(
+                f,
+                "{}: profile version {} is newer than this game supports ({})",
+                path.display(),
+                version,
+                VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::config::AiDifficulty;
+
+    fn store(test: &str) -> (ProfileStore, PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("pong_profiles_{}_{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        (ProfileStore::new(&dir), dir)
+    }
+
+    #[test]
+    fn saves_and_reloads_profiles() {
+        let (store, dir) = store("save");
+        assert_eq!(store.path("Ann Lee!"), dir.join("ann_lee_.json"));
+        assert!(store.load("Ann").unwrap().is_none());
+
+        let mut profile = store.open("Ann").unwrap();
+        profile.stats.record_match(true, AiDifficulty::Hard);
+        store.save(&profile).unwrap();
+        profile.stats.record_return(512.0);
+        store.save(&profile).unwrap();
+
+        assert_eq!(store.load("ann").unwrap(), Some(profile.clone()));
+        let backup = read(&with_suffix(&store.path("Ann"), "bak"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(backup.stats.fastest_return, 0.0);
+        assert!(!with_suffix(&store.path("Ann"), "tmp").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn recovers_from_damaged_files() {
+        let (store, dir) = store("damaged");
+        let mut profile = Profile::new("Ann");
+        profile.stats.matches_played = 3;
+        store.save(&profile).unwrap();
+        profile.stats.matches_played = 4;
+        store.save(&profile).unwrap();
+
+        // A truncated save falls back to the one before it
+        let path = store.path("Ann");
+        std::fs::write(&path, r#"{ "version": 1, "name": "Ann", "sta"#).unwrap();
+        assert_eq!(store.open("Ann").unwrap().stats.matches_played, 3);
+        assert!(with_suffix(&path, "corrupt").exists());
+
+        // With no good copy left it starts again
+        std::fs::write(&path, "garbage").unwrap();
+        std::fs::write(with_suffix(&path, "bak"), "garbage").unwrap();
+        assert_eq!(store.open("Ann").unwrap(), Profile::new("Ann"));
+
+        // Files from a newer version are refused and kept
+        std::fs::write(&path, r#"{ "version": 99, "name": "Ann" }"#).unwrap();
+        assert!(matches!(
+            store.open("Ann"),
+            Err(ProfileError::Version(_, 99))
+        ));
+        assert!(path.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
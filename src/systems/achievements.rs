@@ -0,0 +1,100 @@
+// src/systems/achievements.rs
+use crate::achievements::{Achievement, Context, Toasts, Tracker};
+use crate::components::{GameMode, Score};
+use crate::ecs::{Events, Stage, System, World};
+use crate::engine::Config;
+use crate::engine::date::today;
+use crate::info;
+use crate::profile::Profile;
+use crate::systems::GameEvent;
+
+/// Unlocks achievements on the active `Profile` as the frame's events meet
+/// their conditions, and announces each with a toast if there's a `Toasts`
+/// resource. Add it after `StatsSystem` so stat conditions see the frame's
+/// stats. Does nothing without a `Profile` or in a versus match.
+pub struct AchievementSystem {
+    tracker: Tracker,
+}
+
+impl AchievementSystem {
+    pub fn new(achievements: Vec<Achievement>) -> Self {
+        Self {
+            tracker: Tracker::new(achievements),
+        }
+    }
+}
+
+impl System for AchievementSystem {
+    fn name(&self) -> &'static str {
+        "achievements"
+    }
+
+    fn stage(&self) -> Stage {
+        Stage::PostUpdate
+    }
+
+    fn run(&mut self, world: &mut World, config: &Config, _delta_time: f32) {
+        if !world.has_resource::<Profile>() || GameMode::of(world) == GameMode::Versus {
+            return;
+        }
+
+        let mut profile = world.resource_mut::<Profile>();
+        let unlocked: Vec<Achievement> = {
+            let score = world.resource::<Score>();
+            let context = Context {
+                difficulty: config.ai_difficulty,
+                max_ball_speed: config.ball_max_speed,
+                score: &score,
+                stats: &profile.stats,
+            };
+            let events = world.resource::<Events<GameEvent>>();
+            self.tracker
+                .process(events.iter(), &context)
+                .into_iter()
+                .filter(|achievement| !profile.achievements.contains_key(&achievement.id))
+                .cloned()
+                .collect()
+        };
+
+        for achievement in unlocked {
+            info!("achievement unlocked"; id = achievement.id, profile = profile.name);
+            profile.achievements.insert(achievement.id.clone(), today());
+            if world.has_resource::<Toasts>() {
+                world
+                    .resource_mut::<Toasts>()
+                    .push("ACHIEVEMENT UNLOCKED", achievement.name.to_uppercase());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::achievements;
+
+    #[test]
+    fn unlocks_each_achievement_once() {
+        let mut world = World::new();
+        world.insert_resource(Score {
+            player_score: 10,
+            ai_score: 3,
+        });
+        world.insert_resource(Profile::new("Ann"));
+        world.insert_resource(Toasts::new());
+        let mut events = Events::new();
+        events.send(GameEvent::MatchWon { player_won: true });
+        world.insert_resource(events);
+
+        let mut system = AchievementSystem::new(achievements::defaults());
+        system.run(&mut world, &Config::default(), 0.016);
+        system.run(&mut world, &Config::default(), 0.016);
+
+        let profile = world.resource::<Profile>();
+        let unlocked: Vec<_> = profile.achievements.keys().collect();
+        assert_eq!(unlocked, ["first_win"]);
+        let toasts = world.resource::<Toasts>();
+        let shown: Vec<_> = toasts.shown().map(|toast| toast.text.as_str()).collect();
+        assert_eq!(shown, ["FIRST WIN"]);
+    }
+}
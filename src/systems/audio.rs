@@ -0,0 +1,120 @@
+// src/systems/audio.rs
+use crate::audio::{Audio, Sound};
+use crate::ecs::{Events, Stage, System, World};
+use crate::engine::Config;
+use crate::systems::GameEvent;
+
+/// Plays a sound for each gameplay event of the frame.
+pub struct AudioSystem {
+    audio: Audio,
+}
+
+impl AudioSystem {
+    pub fn new(audio: Audio) -> Self {
+        Self { audio }
+    }
+
+    fn sound_for(event: &GameEvent) -> Sound {
+        match event {
+            GameEvent::PaddleHit { .. } => Sound::PaddleHit,
+            GameEvent::WallBounce { .. } => Sound::WallBounce,
+            GameEvent::Goal { .. } => Sound::Score,
+            GameEvent::MatchWon { .. } => Sound::Win,
+        }
+    }
+}
+
+impl System for AudioSystem {
+    fn name(&self) -> &'static str {
+        "audio"
+    }
+
+    fn stage(&self) -> Stage {
+        Stage::PostUpdate
+    }
+
+    fn run(&mut self, world: &mut World, config: &Config, _delta_time: f32) {
+        // Follow the config so volume and mute can change while playing
+        self.audio.volume = config.audio_volume;
+        self.audio.muted = config.audio_muted;
+
+        let events = world.resource::<Events<GameEvent>>();
+        // The winning point plays the fanfare instead of the score beep
+        let match_won = events
+            .iter()
+            .any(|e| matches!(e, GameEvent::MatchWon { .. }));
+
+        for event in events.iter() {
+            if match_won && matches!(event, GameEvent::Goal { .. }) {
+                continue;
+            }
+            self.audio.play(Self::sound_for(event));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::NullBackend;
+
+    fn world_with(events: Vec<GameEvent>) -> World {
+        let mut queue = Events::new();
+        for event in events {
+            queue.send(event);
+        }
+        let mut world = World::new();
+        world.insert_resource(queue);
+        world
+    }
+
+    #[test]
+    fn events_play_matching_sounds() {
+        let backend = NullBackend::new();
+        let log = backend.log();
+        let mut system = AudioSystem::new(Audio::new(Box::new(backend)));
+        let mut world = world_with(vec![
+            GameEvent::PaddleHit { x: 0.0, y: 0.0, speed: 200.0, is_player: true, rally: 1 },
+            GameEvent::WallBounce { x: 0.0, y: 0.0 },
+            GameEvent::Goal { x: 0.0, y: 0.0, player_scored: true, rally: 1 },
+        ]);
+
+        system.run(&mut world, &Config::default(), 0.016);
+
+        assert_eq!(
+            *log.borrow(),
+            vec![Sound::PaddleHit, Sound::WallBounce, Sound::Score]
+        );
+    }
+
+    #[test]
+    fn winning_point_plays_only_the_fanfare() {
+        let backend = NullBackend::new();
+        let log = backend.log();
+        let mut system = AudioSystem::new(Audio::new(Box::new(backend)));
+        let mut world = world_with(vec![
+            GameEvent::Goal { x: 0.0, y: 0.0, player_scored: false, rally: 0 },
+            GameEvent::MatchWon { player_won: false },
+        ]);
+
+        system.run(&mut world, &Config::default(), 0.016);
+
+        assert_eq!(*log.borrow(), vec![Sound::Win]);
+    }
+
+    #[test]
+    fn config_mute_silences_events() {
+        let backend = NullBackend::new();
+        let log = backend.log();
+        let mut system = AudioSystem::new(Audio::new(Box::new(backend)));
+        let mut world = world_with(vec![GameEvent::WallBounce { x: 0.0, y: 0.0 }]);
+        let config = Config {
+            audio_muted: true,
+            ..Config::default()
+        };
+
+        system.run(&mut world, &config, 0.016);
+
+        assert!(log.borrow().is_empty());
+    }
+}
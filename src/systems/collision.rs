@@ -1,6 +1,9 @@
 // src/systems/collision.rs
-use crate::components::{Ball, Paddle};
+use crate::components::{Ball, DebugInfo, Paddle, Rally};
+use crate::ecs::{Events, Stage, System, World};
 use crate::engine::Config;
+use crate::systems::GameEvent;
+use crate::trace;
 
 pub struct CollisionSystem;
 
@@ -10,47 +13,143 @@ impl CollisionSystem {
         Self
     }
 
-    pub fn update(
-        &self,
-        ball: &mut Ball,
-        player_paddle: &Paddle,
-        ai_paddle: &Paddle,
-        config: &Config,
-    ) {
-        // Wall collisions (top and bottom)
-        if ball.y <= config.ball_size / 2.0
-            || ball.y >= config.window_height as f32 - config.ball_size / 2.0
-        {
+    /// Returns true if the ball bounced off the top or bottom wall.
+    fn check_wall_collision(&self, ball: &mut Ball, config: &Config) -> bool {
+        // Wall collisions (top and bottom), only while moving into the wall
+        let hit_top = ball.y <= config.ball_radius && ball.velocity_y < 0.0;
+        let hit_bottom =
+            ball.y >= config.window_height as f32 - config.ball_radius && ball.velocity_y > 0.0;
+
+        if hit_top || hit_bottom {
             ball.velocity_y = -ball.velocity_y;
-        }
 
-        // Paddle collisions
-        self.check_paddle_collision(ball, player_paddle, config);
-        self.check_paddle_collision(ball, ai_paddle, config);
+            // Friction against the spinning surface kicks the ball sideways,
+            // which changes the angle it leaves at. A clockwise ball slides
+            // right along the top wall and left along the bottom one.
+            let kick = ball.spin * config.wall_spin_friction;
+            ball.velocity_x += if hit_top { -kick } else { kick };
+            ball.spin *= config.spin_bounce_retention;
+        }
+        hit_top || hit_bottom
     }
 
-    fn check_paddle_collision(&self, ball: &mut Ball, paddle: &Paddle, config: &Config) {
-        let ball_left = ball.x - config.ball_size / 2.0;
-        let ball_right = ball.x + config.ball_size / 2.0;
-        let ball_top = ball.y - config.ball_size / 2.0;
-        let ball_bottom = ball.y + config.ball_size / 2.0;
-
+    /// Circle-vs-rectangle test. Returns the contact normal, pointing from the
+    /// paddle towards the ball, and how deep the ball has sunk in.
+    fn paddle_contact(&self, ball: &Ball, paddle: &Paddle, config: &Config) -> Option<(f32, f32, f32)> {
+        let radius = config.ball_radius;
         let paddle_left = paddle.x;
         let paddle_right = paddle.x + config.paddle_width;
         let paddle_top = paddle.y;
         let paddle_bottom = paddle.y + config.paddle_height;
 
-        if ball_right >= paddle_left
-            && ball_left <= paddle_right
-            && ball_bottom >= paddle_top
-            && ball_top <= paddle_bottom
-        {
-            ball.velocity_x = -ball.velocity_x;
+        // Closest point of the paddle to the ball centre. On a face it gives a
+        // straight normal, on a corner a diagonal one.
+        let closest_x = ball.x.clamp(paddle_left, paddle_right);
+        let closest_y = ball.y.clamp(paddle_top, paddle_bottom);
+        let dx = ball.x - closest_x;
+        let dy = ball.y - closest_y;
+        let distance_sq = dx * dx + dy * dy;
 
+        if distance_sq > radius * radius {
+            return None;
+        }
+
+        if distance_sq > 0.0 {
+            let distance = distance_sq.sqrt();
+            return Some((dx / distance, dy / distance, radius - distance));
+        }
+
+        // The centre is inside the paddle, push out through the nearest side
+        let sides = [
+            (-1.0, 0.0, ball.x - paddle_left),
+            (1.0, 0.0, paddle_right - ball.x),
+            (0.0, -1.0, ball.y - paddle_top),
+            (0.0, 1.0, paddle_bottom - ball.y),
+        ];
+        sides
+            .into_iter()
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(nx, ny, depth)| (nx, ny, depth + radius))
+    }
+
+    /// Bounces the ball off the paddle. Returns the contact normal, and
+    /// whether the ball was returned: hits on the top and bottom faces only
+    /// deflect it, with no speed-up and no steering kick.
+    fn check_paddle_collision(
+        &self,
+        ball: &mut Ball,
+        paddle: &Paddle,
+        config: &Config,
+    ) -> Option<(f32, f32, bool)> {
+        let (normal_x, normal_y, depth) = self.paddle_contact(ball, paddle, config)?;
+
+        // Only bounce balls heading into the paddle, otherwise a ball that
+        // is still overlapping after the bounce would flip back every frame
+        let along_normal = ball.velocity_x * normal_x + ball.velocity_y * normal_y;
+        if along_normal >= 0.0 {
+            return None;
+        }
+
+        let returned = normal_x != 0.0;
+        let speed = (ball.speed() * config.ball_speed_up).min(config.ball_max_speed);
+
+        // Move the ball out of the paddle and reflect it about the contact normal
+        ball.x += normal_x * depth;
+        ball.y += normal_y * depth;
+        ball.velocity_x -= 2.0 * along_normal * normal_x;
+        ball.velocity_y -= 2.0 * along_normal * normal_y;
+
+        if returned {
             // Add some variation to the bounce angle based on where the ball hits the paddle
             let paddle_center = paddle.y + config.paddle_height / 2.0;
             let hit_pos = (ball.y - paddle_center) / (config.paddle_height / 2.0);
-            ball.velocity_y += hit_pos * 100.0;
+            ball.velocity_y += hit_pos.clamp(-1.0, 1.0) * 100.0;
+        }
+
+        // A moving paddle drags the ball along and puts spin on it. The
+        // spin is signed by the direction of travel so the ball curves
+        // the way the paddle moved on either side of the court.
+        let side = if ball.velocity_x > 0.0 { 1.0 } else { -1.0 };
+        ball.velocity_y += paddle.velocity_y * config.paddle_velocity_transfer;
+        ball.spin = (ball.spin + side * paddle.velocity_y * config.spin_transfer)
+            .clamp(-config.max_spin, config.max_spin);
+
+        // The hit only steers the ball, its speed grows by a fixed factor
+        if returned {
+            ball.set_speed(speed);
+        }
+        ball.limit_velocity(config.ball_max_speed, config.ball_max_angle.to_radians());
+        Some((normal_x, normal_y, returned))
+    }
+
+    /// Elastic collision between two equally heavy balls.
+    fn check_ball_collision(&self, a: &mut Ball, b: &mut Ball, config: &Config) {
+        let dx = b.x - a.x;
+        let dy = b.y - a.y;
+        let distance = (dx * dx + dy * dy).sqrt();
+        let min_distance = config.ball_radius * 2.0;
+
+        if distance >= min_distance || distance == 0.0 {
+            return;
+        }
+
+        let nx = dx / distance;
+        let ny = dy / distance;
+
+        // Push the balls apart so they don't stay stuck together
+        let overlap = (min_distance - distance) / 2.0;
+        a.x -= nx * overlap;
+        a.y -= ny * overlap;
+        b.x += nx * overlap;
+        b.y += ny * overlap;
+
+        // Exchange the velocity components along the normal if they are closing
+        let closing = (a.velocity_x - b.velocity_x) * nx + (a.velocity_y - b.velocity_y) * ny;
+        if closing > 0.0 {
+            a.velocity_x -= closing * nx;
+            a.velocity_y -= closing * ny;
+            b.velocity_x += closing * nx;
+            b.velocity_y += closing * ny;
         }
     }
 
@@ -62,3 +161,123 @@ impl CollisionSystem {
         }
     }
 }
+
+impl System for CollisionSystem {
+    fn name(&self) -> &'static str {
+        "collision"
+    }
+
+    fn stage(&self) -> Stage {
+        Stage::Collision
+    }
+
+    fn run(&mut self, world: &mut World, config: &Config, _delta_time: f32) {
+        let paddles = world.components::<Paddle>();
+        let mut balls = world.components_mut::<Ball>();
+        let mut rally = world.resource_mut::<Rally>();
+        let mut events = world.resource_mut::<Events<GameEvent>>();
+        let mut debug = world
+            .has_resource::<DebugInfo>()
+            .then(|| world.resource_mut::<DebugInfo>());
+
+        for (_, ball) in balls.iter_mut() {
+            if self.check_wall_collision(ball, config) {
+                events.send(GameEvent::WallBounce {
+                    x: ball.x,
+                    y: ball.y,
+                });
+                if let Some(debug) = &mut debug {
+                    let (normal_y, wall_y) = if ball.velocity_y > 0.0 {
+                        (1.0, 0.0)
+                    } else {
+                        (-1.0, config.window_height as f32)
+                    };
+                    debug.add_contact(ball.x, wall_y, 0.0, normal_y);
+                }
+            }
+
+            // Paddle collisions
+            for (_, paddle) in paddles.iter() {
+                if let Some((normal_x, normal_y, returned)) =
+                    self.check_paddle_collision(ball, paddle, config)
+                {
+                    if let Some(debug) = &mut debug {
+                        let radius = config.ball_radius;
+                        debug.add_contact(
+                            ball.x - normal_x * radius,
+                            ball.y - normal_y * radius,
+                            normal_x,
+                            normal_y,
+                        );
+                    }
+                    if !returned {
+                        continue;
+                    }
+                    rally.hit();
+                    trace!("paddle hit"; player = paddle.is_player, speed = ball.speed(), rally = rally.hits);
+                    events.send(GameEvent::PaddleHit {
+                        x: ball.x,
+                        y: ball.y,
+                        speed: ball.speed(),
+                        is_player: paddle.is_player,
+                        rally: rally.hits,
+                    });
+                }
+            }
+        }
+
+        if config.ball_collisions {
+            let entities = balls.entities();
+            for (i, &first) in entities.iter().enumerate() {
+                for &second in &entities[i + 1..] {
+                    if let Some((a, b)) = balls.pair_mut(first, second) {
+                        self.check_ball_collision(a, b, config);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(ball: Ball) -> (Ball, u32, usize) {
+        let mut world = World::new();
+        world.register::<Ball>();
+        world.register::<Paddle>();
+        world.spawn_with(ball);
+        world.spawn_with(Paddle::new(50.0, 250.0, true));
+        world.insert_resource(Rally::new());
+        world.insert_resource(Events::<GameEvent>::new());
+        CollisionSystem::new().run(&mut world, &Config::default(), 0.016);
+
+        let ball = world.components::<Ball>().iter().next().unwrap().1.clone();
+        let hits = world.resource::<Rally>().hits;
+        let events = world.resource::<Events<GameEvent>>().iter().count();
+        (ball, hits, events)
+    }
+
+    #[test]
+    fn only_front_face_hits_are_returns() {
+        let config = Config::default();
+
+        // Into the front face: reflected, sped up and counted
+        let mut ball = Ball::new(50.0 + config.paddle_width + 3.0, 300.0, 300.0);
+        (ball.velocity_x, ball.velocity_y) = (-300.0, 0.0);
+        let (ball, hits, events) = run(ball);
+        assert!(ball.velocity_x > 0.0);
+        assert!(ball.speed() > 300.0);
+        assert_eq!((hits, events), (1, 1));
+
+        // Onto the top face: deflected, but still heading for the goal
+        let mut ball = Ball::new(55.0, 247.0, 300.0);
+        (ball.velocity_x, ball.velocity_y) = (-100.0, 280.0);
+        let speed = ball.speed();
+        let (ball, hits, events) = run(ball);
+        assert!(ball.velocity_y < 0.0 && ball.velocity_x < 0.0);
+        assert!(ball.speed() <= speed + 1e-3);
+        assert_eq!((hits, events), (0, 0));
+    }
+}
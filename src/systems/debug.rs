@@ -0,0 +1,39 @@
+// src/systems/debug.rs
+use crate::components::DebugInfo;
+use crate::ecs::{Stage, System, World};
+use crate::engine::Config;
+
+/// Counts simulation ticks and ages the contacts recorded for the debug
+/// overlay. Does nothing without a `DebugInfo` resource.
+pub struct DebugSystem;
+
+impl DebugSystem {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl System for DebugSystem {
+    fn name(&self) -> &'static str {
+        "debug"
+    }
+
+    fn stage(&self) -> Stage {
+        Stage::PreUpdate
+    }
+
+    fn run(&mut self, world: &mut World, _config: &Config, delta_time: f32) {
+        if !world.has_resource::<DebugInfo>() {
+            return;
+        }
+
+        let mut debug = world.resource_mut::<DebugInfo>();
+        debug.tick += 1;
+        for contact in debug.contacts.iter_mut() {
+            contact.age += delta_time;
+        }
+        debug
+            .contacts
+            .retain(|contact| contact.age < DebugInfo::CONTACT_LIFETIME);
+    }
+}
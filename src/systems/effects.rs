@@ -0,0 +1,294 @@
+// src/systems/effects.rs
+use crate::components::{Ball, Particle, Particles, ScreenShake, Trail};
+use crate::ecs::{Events, Stage, System, World};
+use crate::engine::Config;
+use crate::systems::GameEvent;
+
+const PARTICLE_DRAG: f32 = 2.0; // Fraction of velocity lost per second
+const SHAKE_DURATION: f32 = 0.3;
+
+/// Spawns particles for gameplay events, ages them, records ball trails and
+/// drives the screen shake.
+pub struct EffectsSystem {
+    rng: u32,
+}
+
+impl EffectsSystem {
+    pub fn new() -> Self {
+        Self { rng: 0x9E37_79B9 }
+    }
+
+    // Xorshift, plenty for scattering sparks
+    fn random(&mut self) -> f32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        (self.rng >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Random value in `[-1, 1)`.
+    fn random_signed(&mut self) -> f32 {
+        self.random() * 2.0 - 1.0
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn burst(
+        &mut self,
+        particles: &mut Particles,
+        x: f32,
+        y: f32,
+        count: usize,
+        speed: f32,
+        lifetime: f32,
+        start_color: u32,
+        end_color: u32,
+    ) {
+        for _ in 0..count {
+            let angle = self.random() * std::f32::consts::TAU;
+            let speed = speed * (0.3 + 0.7 * self.random());
+            particles.particles.push(Particle {
+                x,
+                y,
+                velocity_x: angle.cos() * speed,
+                velocity_y: angle.sin() * speed,
+                age: 0.0,
+                lifetime: lifetime * (0.5 + 0.5 * self.random()),
+                size: 1.0 + self.random() * 2.0,
+                start_color,
+                end_color,
+            });
+        }
+    }
+
+    fn emit(&mut self, event: &GameEvent, particles: &mut Particles, config: &Config) {
+        let width = config.window_width as f32;
+        let height = config.window_height as f32;
+        match *event {
+            GameEvent::PaddleHit { x, y, speed, .. } => {
+                let count = 8 + (speed / 50.0) as usize;
+                self.burst(particles, x, y, count, speed * 0.6, 0.4, 0xFFFFFF, 0x00FFFF);
+            }
+            GameEvent::WallBounce { x, y } => {
+                self.burst(particles, x, y, 6, 120.0, 0.25, 0xCCCCCC, 0x444444);
+            }
+            GameEvent::Goal {
+                x,
+                y,
+                player_scored,
+                ..
+            } => {
+                // The ball is already off screen, burst from the goal line
+                let x = x.clamp(0.0, width - 1.0);
+                let y = y.clamp(0.0, height - 1.0);
+                let color = if player_scored { 0x00FF00 } else { 0xFF4444 };
+                self.burst(particles, x, y, 40, 300.0, 0.8, color, 0x000000);
+            }
+            GameEvent::MatchWon { .. } => {}
+        }
+    }
+
+    fn update_particles(particles: &mut Particles, delta_time: f32) {
+        let drag = (1.0 - PARTICLE_DRAG * delta_time).max(0.0);
+        for particle in particles.particles.iter_mut() {
+            particle.x += particle.velocity_x * delta_time;
+            particle.y += particle.velocity_y * delta_time;
+            particle.velocity_x *= drag;
+            particle.velocity_y *= drag;
+            particle.age += delta_time;
+        }
+        particles.particles.retain(Particle::is_alive);
+    }
+
+    fn update_trails(world: &mut World, config: &Config) {
+        // Give new balls (serves, multiball) a trail to record into
+        let untracked: Vec<_> = {
+            let trails = world.components::<Trail>();
+            world
+                .components::<Ball>()
+                .iter()
+                .map(|(entity, _)| entity)
+                .filter(|&entity| !trails.contains(entity))
+                .collect()
+        };
+        for entity in untracked {
+            world.insert(entity, Trail::default());
+        }
+
+        world.query2::<Trail, Ball>(|_, trail, ball| {
+            // A ball that jumped (a serve) starts a fresh trail
+            if let Some(&(last_x, last_y)) = trail.points.front() {
+                let jump = (ball.x - last_x).abs() + (ball.y - last_y).abs();
+                if jump > config.window_width as f32 / 4.0 {
+                    trail.points.clear();
+                }
+            }
+            trail.points.push_front((ball.x, ball.y));
+            trail.points.truncate(config.ball_trail_length);
+        });
+    }
+
+    fn update_shake(&mut self, shake: &mut ScreenShake, delta_time: f32) {
+        shake.time_left = (shake.time_left - delta_time).max(0.0);
+        if shake.time_left <= 0.0 || shake.duration <= 0.0 {
+            shake.offset = (0, 0);
+            return;
+        }
+        let strength = shake.intensity * shake.time_left / shake.duration;
+        shake.offset = (
+            (self.random_signed() * strength).round() as i32,
+            (self.random_signed() * strength).round() as i32,
+        );
+    }
+}
+
+impl System for EffectsSystem {
+    fn name(&self) -> &'static str {
+        "effects"
+    }
+
+    fn stage(&self) -> Stage {
+        Stage::PostUpdate
+    }
+
+    fn run(&mut self, world: &mut World, config: &Config, delta_time: f32) {
+        {
+            let events = world.resource::<Events<GameEvent>>();
+            let mut particles = world.resource_mut::<Particles>();
+            let mut shake = world.resource_mut::<ScreenShake>();
+
+            for event in events.iter() {
+                if config.particles_enabled {
+                    self.emit(event, &mut particles, config);
+                }
+                if config.screen_shake_enabled && matches!(event, GameEvent::Goal { .. }) {
+                    shake.start(config.screen_shake_intensity, SHAKE_DURATION);
+                }
+            }
+
+            Self::update_particles(&mut particles, delta_time);
+            if !config.screen_shake_enabled {
+                shake.time_left = 0.0;
+            }
+            self.update_shake(&mut shake, delta_time);
+        }
+
+        if config.ball_trail_enabled {
+            Self::update_trails(world, config);
+        } else {
+            for (_, trail) in world.components_mut::<Trail>().iter_mut() {
+                trail.points.clear();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::events::testing::{goal, hit};
+
+    fn world_with(events: Vec<GameEvent>) -> World {
+        let mut world = World::new();
+        world.register::<Ball>();
+        world.register::<Trail>();
+        world.insert_resource(Particles::default());
+        world.insert_resource(ScreenShake::default());
+        let mut queue = Events::<GameEvent>::new();
+        for event in events {
+            queue.send(event);
+        }
+        world.insert_resource(queue);
+        world
+    }
+
+    fn run_frame(system: &mut EffectsSystem, world: &mut World, config: &Config, delta_time: f32) {
+        system.run(world, config, delta_time);
+        world.resource_mut::<Events<GameEvent>>().clear();
+    }
+
+    #[test]
+    fn particles_age_and_expire() {
+        let config = Config::default();
+        let mut world = world_with(vec![hit(300.0, true, 1)]);
+        let mut system = EffectsSystem::new();
+
+        run_frame(&mut system, &mut world, &config, 0.1);
+        let particles = world.resource::<Particles>().particles.clone();
+        assert_eq!(particles.len(), 8 + 6);
+        for particle in &particles {
+            assert!(particle.is_alive());
+            assert!(particle.progress() > 0.0 && particle.progress() <= 0.5);
+        }
+
+        // Hit sparks live at most 0.4 seconds
+        run_frame(&mut system, &mut world, &config, 0.2);
+        run_frame(&mut system, &mut world, &config, 0.11);
+        assert!(world.resource::<Particles>().particles.is_empty());
+    }
+
+    #[test]
+    fn trails_keep_the_newest_positions() {
+        let config = Config {
+            ball_trail_length: 3,
+            ..Config::default()
+        };
+        let mut world = world_with(Vec::new());
+        let ball = world.spawn_with(Ball::new(100.0, 300.0, 300.0));
+        let mut system = EffectsSystem::new();
+
+        for frame in 1..=5 {
+            world.components_mut::<Ball>().get_mut(ball).unwrap().x = 100.0 + frame as f32;
+            run_frame(&mut system, &mut world, &config, 0.016);
+        }
+        let trails = world.components::<Trail>();
+        let points: Vec<_> = trails.get(ball).unwrap().points.iter().copied().collect();
+        assert_eq!(points, [(105.0, 300.0), (104.0, 300.0), (103.0, 300.0)]);
+    }
+
+    #[test]
+    fn shake_decays_to_zero() {
+        let config = Config::default();
+        let mut world = world_with(vec![goal(true, 0)]);
+        let mut system = EffectsSystem::new();
+
+        let mut last_time_left = SHAKE_DURATION;
+        for _ in 0..5 {
+            run_frame(&mut system, &mut world, &config, 0.05);
+            let shake = world.resource::<ScreenShake>().clone();
+            assert!(shake.time_left < last_time_left);
+            let strength = config.screen_shake_intensity * shake.time_left / SHAKE_DURATION;
+            assert!(shake.offset.0.abs() as f32 <= strength.round());
+            assert!(shake.offset.1.abs() as f32 <= strength.round());
+            last_time_left = shake.time_left;
+        }
+        run_frame(&mut system, &mut world, &config, 0.1);
+        let shake = world.resource::<ScreenShake>().clone();
+        assert_eq!((shake.time_left, shake.offset), (0.0, (0, 0)));
+    }
+
+    #[test]
+    fn disabled_effects_do_nothing() {
+        let mut config = Config::default();
+        let mut world = world_with(vec![hit(300.0, true, 1), goal(false, 1)]);
+        let ball = world.spawn_with(Ball::new(100.0, 300.0, 300.0));
+        let mut system = EffectsSystem::new();
+
+        config.particles_enabled = false;
+        run_frame(&mut system, &mut world, &config, 0.016);
+        assert!(world.resource::<Particles>().particles.is_empty());
+        assert!(world.resource::<ScreenShake>().time_left > 0.0);
+        assert_eq!(world.components::<Trail>().get(ball).unwrap().points.len(), 1);
+
+        // Turning shake and trails off stops them mid-effect
+        config.screen_shake_enabled = false;
+        config.ball_trail_enabled = false;
+        run_frame(&mut system, &mut world, &config, 0.016);
+        let shake = world.resource::<ScreenShake>().clone();
+        assert_eq!((shake.time_left, shake.offset), (0.0, (0, 0)));
+        assert!(world.components::<Trail>().get(ball).unwrap().points.is_empty());
+
+        world.resource_mut::<Events<GameEvent>>().send(goal(true, 0));
+        run_frame(&mut system, &mut world, &config, 0.016);
+        assert_eq!(world.resource::<ScreenShake>().time_left, 0.0);
+    }
+}
@@ -0,0 +1,36 @@
+// src/systems/events.rs
+/// Gameplay events sent by the systems, consumed by audio and effects.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    // `rally` counts the paddle hits since the last goal, this one included
+    PaddleHit { x: f32, y: f32, speed: f32, is_player: bool, rally: u32 },
+    WallBounce { x: f32, y: f32 },
+    // `rally` is the number of paddle hits in the rally the goal ended
+    Goal { x: f32, y: f32, player_scored: bool, rally: u32 },
+    MatchWon { player_won: bool },
+}
+
+/// Events for tests elsewhere that only care about some of their fields.
+#[cfg(test)]
+pub mod testing {
+    use super::GameEvent;
+
+    pub fn hit(speed: f32, is_player: bool, rally: u32) -> GameEvent {
+        GameEvent::PaddleHit {
+            x: 0.0,
+            y: 0.0,
+            speed,
+            is_player,
+            rally,
+        }
+    }
+
+    pub fn goal(player_scored: bool, rally: u32) -> GameEvent {
+        GameEvent::Goal {
+            x: 0.0,
+            y: 0.0,
+            player_scored,
+            rally,
+        }
+    }
+}
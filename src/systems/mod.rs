@@ -1,9 +1,23 @@
 // src/systems/mod.rs
+pub mod achievements;
+pub mod audio;
 pub mod movement;
 pub mod collision;
+pub mod debug;
+pub mod effects;
+pub mod events;
+pub mod multiball;
 pub mod scoring;
+pub mod stats;
 
+pub use achievements::AchievementSystem;
+pub use audio::AudioSystem;
 pub use movement::MovementSystem;
 pub use collision::CollisionSystem;
+pub use debug::DebugSystem;
+pub use effects::EffectsSystem;
+pub use events::GameEvent;
+pub use multiball::MultiballSystem;
 pub use scoring::ScoringSystem;
+pub use stats::StatsSystem;
 
@@ -1,6 +1,8 @@
 // src/systems/movement.rs
-use crate::components::{Ball, Paddle};
+use crate::components::{Ball, DebugInfo, Intercept, Paddle};
+use crate::ecs::{ComponentStorage, Stage, System, World};
 use crate::engine::Config;
+use crate::engine::config::AiDifficulty;
 
 pub struct MovementSystem;
 
@@ -9,41 +11,169 @@ impl MovementSystem {
         Self
     }
 
-    pub fn update(
-        &self,
-        ball: &mut Ball,
-        player_paddle: &mut Paddle,
-        ai_paddle: &mut Paddle,
-        delta_time: f32,
+    /// Magnus effect: a spinning ball accelerates perpendicular to its
+    /// velocity, curving its path. The spin itself slowly dies down.
+    fn apply_spin(ball: &mut Ball, config: &Config, delta_time: f32) {
+        let k = config.magnus_coefficient * ball.spin;
+        let accel_x = -k * ball.velocity_y;
+        let accel_y = k * ball.velocity_x;
+        ball.velocity_x += accel_x * delta_time;
+        ball.velocity_y += accel_y * delta_time;
+
+        ball.rotation += ball.spin * delta_time;
+        ball.spin *= (1.0 - config.spin_decay * delta_time).max(0.0);
+    }
+
+    /// The ball heading towards the paddle that will reach it first, or the
+    /// closest ball if none is incoming.
+    fn ai_target<'a>(
+        paddle: &Paddle,
+        balls: &'a ComponentStorage<Ball>,
         config: &Config,
-    ) {
-        // Update ball position
-        ball.x += ball.velocity_x * delta_time;
-        ball.y += ball.velocity_y * delta_time;
+    ) -> Option<&'a Ball> {
+        let paddle_x = paddle.x + config.paddle_width / 2.0;
+        let incoming = balls
+            .iter()
+            .filter(|(_, ball)| (paddle_x - ball.x) * ball.velocity_x > 0.0)
+            .min_by(|(_, a), (_, b)| {
+                let time_a = (paddle_x - a.x) / a.velocity_x;
+                let time_b = (paddle_x - b.x) / b.velocity_x;
+                time_a.total_cmp(&time_b)
+            });
 
-        // Update player paddle (keep within bounds)
-        player_paddle.y += player_paddle.velocity_y * delta_time;
+        incoming
+            .or_else(|| {
+                balls.iter().min_by(|(_, a), (_, b)| {
+                    (paddle_x - a.x).abs().total_cmp(&(paddle_x - b.x).abs())
+                })
+            })
+            .map(|(_, ball)| ball)
+    }
 
-        // Constrain player paddle to stay within screen bounds
-        let min_y = 0.0;
-        let max_y = config.window_height as f32 - config.paddle_height;
-        player_paddle.y = player_paddle.y.clamp(min_y, max_y);
-
-        // Simple AI for computer paddle
-        let paddle_center = ai_paddle.y + 50.0;
-        if ball.y < paddle_center - 10.0 {
-            ai_paddle.velocity_y = -200.0;
-        } else if ball.y > paddle_center + 10.0 {
-            ai_paddle.velocity_y = 200.0;
+    /// Where `ball` will cross the face of `paddle`, bouncing off the top and
+    /// bottom walls on the way. Spin is ignored, so curving balls land off
+    /// the prediction.
+    fn predict_intercept(ball: &Ball, paddle: &Paddle, config: &Config) -> Option<Intercept> {
+        let radius = config.ball_radius;
+        let face_x = if ball.velocity_x < 0.0 {
+            paddle.x + config.paddle_width + radius
         } else {
-            ai_paddle.velocity_y = 0.0;
+            paddle.x - radius
+        };
+        let time = (face_x - ball.x) / ball.velocity_x;
+        if !time.is_finite() || time <= 0.0 {
+            return None;
         }
 
-        ai_paddle.y += ai_paddle.velocity_y * delta_time;
+        let top = radius;
+        let bottom = config.window_height as f32 - radius;
+        let (mut x, mut y, mut velocity_y) = (ball.x, ball.y, ball.velocity_y);
+        let mut time_left = time;
+        let mut path = vec![(x, y)];
+
+        // Follow the ball from wall to wall, a steep ball can bounce a lot
+        while path.len() < 32 {
+            let wall_time = match velocity_y {
+                v if v > 0.0 => ((bottom - y) / v).max(0.0),
+                v if v < 0.0 => ((top - y) / v).max(0.0),
+                _ => f32::INFINITY,
+            };
+            if wall_time >= time_left {
+                y += velocity_y * time_left;
+                break;
+            }
+            x += ball.velocity_x * wall_time;
+            y += velocity_y * wall_time;
+            velocity_y = -velocity_y;
+            time_left -= wall_time;
+            path.push((x, y));
+        }
+
+        let y = y.clamp(top, bottom);
+        path.push((face_x, y));
+        Some(Intercept { x: face_x, y, path })
+    }
+}
+
+impl System for MovementSystem {
+    fn name(&self) -> &'static str {
+        "movement"
+    }
+
+    fn stage(&self) -> Stage {
+        Stage::Movement
+    }
+
+    fn run(&mut self, world: &mut World, config: &Config, delta_time: f32) {
+        // Update ball positions
+        let mut balls = world.components_mut::<Ball>();
+        for (_, ball) in balls.iter_mut() {
+            Self::apply_spin(ball, config, delta_time);
+            // Spin can bend the ball towards vertical, keep it playable
+            ball.limit_velocity(config.ball_max_speed, config.ball_max_angle.to_radians());
+            ball.x += ball.velocity_x * delta_time;
+            ball.y += ball.velocity_y * delta_time;
+        }
 
-        // Constrain AI paddle to stay within screen bounds
         let min_y = 0.0;
         let max_y = config.window_height as f32 - config.paddle_height;
-        ai_paddle.y = ai_paddle.y.clamp(min_y, max_y);
+
+        let mut intercepts = Vec::new();
+        for (_, paddle) in world.components_mut::<Paddle>().iter_mut() {
+            if !paddle.is_player && !paddle.is_second_player {
+                // Simple AI for computer paddle, tracking the most urgent ball.
+                // On hard it heads for where that ball will arrive instead.
+                let target = Self::ai_target(paddle, &balls, config);
+                let intercept = target.and_then(|ball| Self::predict_intercept(ball, paddle, config));
+                let target_y = match (config.ai_difficulty, &intercept) {
+                    (AiDifficulty::Hard, Some(intercept)) => Some(intercept.y),
+                    _ => target.map(|ball| ball.y),
+                };
+                intercepts.extend(intercept);
+
+                let speed = config.ai_difficulty.paddle_speed();
+                let paddle_center = paddle.y + 50.0;
+                paddle.velocity_y = match target_y {
+                    Some(y) if y < paddle_center - 10.0 => -speed,
+                    Some(y) if y > paddle_center + 10.0 => speed,
+                    _ => 0.0,
+                };
+            }
+
+            paddle.y += paddle.velocity_y * delta_time;
+
+            // Constrain paddle to stay within screen bounds
+            paddle.y = paddle.y.clamp(min_y, max_y);
+        }
+
+        if world.has_resource::<DebugInfo>() {
+            world.resource_mut::<DebugInfo>().intercepts = intercepts;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predicts_intercept_through_a_wall_bounce() {
+        let config = Config::default();
+        let paddle = Paddle::new(750.0, 250.0, false);
+        let mut ball = Ball::new(400.0, 500.0, 0.0);
+        ball.velocity_x = 300.0;
+        ball.velocity_y = 300.0;
+
+        let intercept = MovementSystem::predict_intercept(&ball, &paddle, &config).unwrap();
+        let face_x = 750.0 - config.ball_radius;
+        let bottom = 600.0 - config.ball_radius;
+        assert_eq!(intercept.x, face_x);
+        // 45 degrees: the distance past the bottom wall comes back up
+        let expected_y = bottom - ((face_x - 400.0) - (bottom - 500.0));
+        assert!((intercept.y - expected_y).abs() < 1e-3);
+        assert_eq!(intercept.path.len(), 3);
+
+        ball.velocity_x = -300.0;
+        assert!(MovementSystem::predict_intercept(&ball, &paddle, &config).is_none());
     }
 }
@@ -0,0 +1,60 @@
+// src/systems/multiball.rs
+use crate::components::{Ball, ExtraBall, Rally};
+use crate::ecs::{Stage, System, World};
+use crate::engine::Config;
+use crate::debug;
+
+/// Serves an extra ball every `multiball_rally_hits` paddle hits in a rally.
+pub struct MultiballSystem {
+    spawned_at_hits: u32,
+}
+
+impl MultiballSystem {
+    pub fn new() -> Self {
+        Self { spawned_at_hits: 0 }
+    }
+}
+
+impl System for MultiballSystem {
+    fn name(&self) -> &'static str {
+        "multiball"
+    }
+
+    fn stage(&self) -> Stage {
+        Stage::PostUpdate
+    }
+
+    fn run(&mut self, world: &mut World, config: &Config, _delta_time: f32) {
+        let hits = world.resource::<Rally>().hits;
+        if hits < self.spawned_at_hits {
+            // A point was scored, start counting for the next rally
+            self.spawned_at_hits = 0;
+        }
+
+        if !config.multiball_enabled || config.multiball_rally_hits == 0 {
+            return;
+        }
+
+        let ball_count = world.components::<Ball>().len() as u32;
+        let due = hits >= self.spawned_at_hits + config.multiball_rally_hits;
+        if !due || ball_count >= config.multiball_max_balls {
+            return;
+        }
+
+        let mut ball = Ball::new(
+            config.window_width as f32 / 2.0,
+            config.window_height as f32 / 2.0,
+            config.ball_speed,
+        );
+        // Alternate the serve direction so extra balls fan out
+        if ball_count % 2 == 1 {
+            ball.velocity_x = -ball.velocity_x;
+            ball.velocity_y = -ball.velocity_y;
+        }
+
+        let entity = world.spawn_with(ball);
+        world.insert(entity, ExtraBall);
+        debug!("extra ball served"; balls = ball_count + 1, rally = hits);
+        self.spawned_at_hits = hits;
+    }
+}
@@ -1,6 +1,9 @@
 // src/systems/scoring.rs
-use crate::components::{Ball, Score};
+use crate::components::{Ball, ExtraBall, Rally, Score};
+use crate::ecs::{Events, Stage, System, World};
 use crate::engine::Config;
+use crate::systems::GameEvent;
+use crate::info;
 
 pub struct ScoringSystem;
 
@@ -8,16 +11,141 @@ impl ScoringSystem {
     pub fn new() -> Self {
         Self
     }
+}
 
-    pub fn update(&mut self, ball: &mut Ball, score: &mut Score, config: &Config) {
-        // Check if ball went off screen
-        if ball.x < 0.0 {
-            score.ai_scores();
-            ball.reset(config.window_width as f32 / 2.0, config.window_height as f32 / 2.0);
-        } else if ball.x > config.window_width as f32 {
-            score.player_scores();
-            ball.reset(config.window_width as f32 / 2.0, config.window_height as f32 / 2.0);
+impl System for ScoringSystem {
+    fn name(&self) -> &'static str {
+        "scoring"
+    }
+
+    fn stage(&self) -> Stage {
+        Stage::Scoring
+    }
+
+    fn run(&mut self, world: &mut World, config: &Config, _delta_time: f32) {
+        let center_x = config.window_width as f32 / 2.0;
+        let center_y = config.window_height as f32 / 2.0;
+        let mut removed = Vec::new();
+        let mut winner = None; // Whether the player won, once a side has won
+
+        {
+            let mut score = world.resource_mut::<Score>();
+            let mut rally = world.resource_mut::<Rally>();
+            let mut events = world.resource_mut::<Events<GameEvent>>();
+            let extras = world.components::<ExtraBall>();
+
+            // Check if a ball went off screen
+            for (entity, ball) in world.components_mut::<Ball>().iter_mut() {
+                let player_scored = if ball.x < 0.0 {
+                    false
+                } else if ball.x > config.window_width as f32 {
+                    true
+                } else {
+                    continue;
+                };
+
+                // With multiball several balls can go out in one frame; once
+                // one of them has won the match the rest don't count
+                if winner.is_none() {
+                    if player_scored {
+                        score.player_scores();
+                    } else {
+                        score.ai_scores();
+                    }
+                    events.send(GameEvent::Goal {
+                        x: ball.x,
+                        y: ball.y,
+                        player_scored,
+                        rally: rally.hits,
+                    });
+                    info!(
+                        "goal";
+                        scorer = if player_scored { "player" } else { "ai" },
+                        player_score = score.player_score,
+                        ai_score = score.ai_score,
+                        rally = rally.hits
+                    );
+                    let points = if player_scored {
+                        score.player_score
+                    } else {
+                        score.ai_score
+                    };
+                    if points >= config.winning_score {
+                        winner = Some(player_scored);
+                    }
+                }
+
+                rally.reset();
+                // Multiball extras leave play, the original ball is served again
+                if extras.contains(entity) {
+                    removed.push(entity);
+                } else {
+                    ball.reset(center_x, center_y, config.ball_speed);
+                }
+            }
+
+            if let Some(player_won) = winner {
+                info!("match won"; winner = if player_won { "player" } else { "ai" });
+                events.send(GameEvent::MatchWon { player_won });
+            }
+        }
+
+        for entity in removed {
+            world.despawn(entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn world_with_balls(xs: &[f32], score: Score) -> World {
+        let mut world = World::new();
+        world.register::<Ball>();
+        world.register::<ExtraBall>();
+        world.insert_resource(score);
+        world.insert_resource(Rally::new());
+        world.insert_resource(Events::<GameEvent>::new());
+        for &x in xs {
+            world.spawn_with(Ball::new(x, 300.0, 300.0));
         }
+        world
     }
 
-}
\ No newline at end of file
+    fn wins(world: &World) -> Vec<bool> {
+        world
+            .resource::<Events<GameEvent>>()
+            .iter()
+            .filter_map(|event| match *event {
+                GameEvent::MatchWon { player_won } => Some(player_won),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn two_balls_out_in_one_frame_win_once() {
+        let config = Config::default();
+        let mut close = Score::new();
+        close.player_score = config.winning_score - 1;
+        close.ai_score = config.winning_score - 1;
+
+        // Both balls past the AI: the first wins, the second isn't counted
+        let right = config.window_width as f32 + 10.0;
+        let mut world = world_with_balls(&[right, right], close.clone());
+        ScoringSystem::new().run(&mut world, &config, 0.016);
+        assert_eq!(wins(&world), [true]);
+        assert_eq!(world.resource::<Score>().player_score, config.winning_score);
+
+        // One ball out each side: only the first goal counts
+        let mut world = world_with_balls(&[-10.0, right], close);
+        ScoringSystem::new().run(&mut world, &config, 0.016);
+        assert_eq!(wins(&world), [false]);
+        let score = world.resource::<Score>().clone();
+        assert_eq!(
+            (score.player_score, score.ai_score),
+            (config.winning_score - 1, config.winning_score)
+        );
+    }
+}
@@ -0,0 +1,137 @@
+// src/systems/stats.rs
+use crate::components::{GameMode, MatchStats};
+use crate::ecs::{Events, Stage, System, World};
+use crate::engine::Config;
+use crate::profile::Profile;
+use crate::systems::GameEvent;
+
+/// Feeds the frame's gameplay events into the `MatchStats` of the match
+/// in progress and the active `Profile`'s lifetime stats, each only if the
+/// resource exists. Without a `Profile`, as in AI-only matches, or in a
+/// versus match, nothing is counted towards the player.
+pub struct StatsSystem;
+
+impl StatsSystem {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl System for StatsSystem {
+    fn name(&self) -> &'static str {
+        "stats"
+    }
+
+    fn stage(&self) -> Stage {
+        Stage::PostUpdate
+    }
+
+    fn run(&mut self, world: &mut World, config: &Config, delta_time: f32) {
+        let mut returns = Vec::new();
+        let mut points = Vec::new(); // Who scored, and the rally that ended
+        let mut match_won = None;
+        for event in world.resource::<Events<GameEvent>>().iter() {
+            match *event {
+                GameEvent::PaddleHit {
+                    speed, is_player, ..
+                } => {
+                    if is_player {
+                        returns.push(speed);
+                    }
+                }
+                GameEvent::Goal {
+                    player_scored,
+                    rally,
+                    ..
+                } => points.push((player_scored, rally)),
+                GameEvent::MatchWon { player_won } => match_won = Some(player_won),
+                GameEvent::WallBounce { .. } => {}
+            }
+        }
+
+        if world.has_resource::<MatchStats>() {
+            let mut current = world.resource_mut::<MatchStats>();
+            current.duration += delta_time;
+            for &(_, rally) in &points {
+                current.longest_rally = current.longest_rally.max(rally);
+            }
+        }
+
+        if world.has_resource::<Profile>() && GameMode::of(world) == GameMode::Single {
+            let stats = &mut world.resource_mut::<Profile>().stats;
+            stats.time_played += delta_time as f64;
+            for speed in returns {
+                stats.record_return(speed);
+            }
+            for (player_scored, rally) in points {
+                stats.record_point(player_scored, rally);
+            }
+            if let Some(player_won) = match_won {
+                stats.record_match(player_won, config.ai_difficulty);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::config::AiDifficulty;
+    use crate::systems::events::testing::{goal, hit};
+
+    fn run_frame(system: &mut StatsSystem, world: &mut World, events: Vec<GameEvent>) {
+        let mut queue = world.resource_mut::<Events<GameEvent>>();
+        queue.clear();
+        for event in events {
+            queue.send(event);
+        }
+        drop(queue);
+        let config = Config {
+            ai_difficulty: AiDifficulty::Hard,
+            ..Config::default()
+        };
+        system.run(world, &config, 0.5);
+    }
+
+    #[test]
+    fn turns_events_into_stats() {
+        let mut world = World::new();
+        world.insert_resource(Events::<GameEvent>::new());
+        let mut system = StatsSystem::new();
+
+        // Without a profile nothing is counted
+        run_frame(&mut system, &mut world, vec![goal(true, 0)]);
+
+        world.insert_resource(Profile::new("Ann"));
+        world.insert_resource(MatchStats::new());
+        run_frame(
+            &mut system,
+            &mut world,
+            vec![hit(250.0, true, 1), hit(900.0, false, 2)],
+        );
+        run_frame(&mut system, &mut world, vec![hit(320.0, true, 3), goal(true, 3)]);
+        run_frame(&mut system, &mut world, vec![hit(200.0, true, 1), goal(false, 1)]);
+        run_frame(
+            &mut system,
+            &mut world,
+            vec![goal(true, 0), GameEvent::MatchWon { player_won: true }],
+        );
+
+        let current = world.resource::<MatchStats>().clone();
+        assert_eq!((current.longest_rally, current.duration), (3, 2.0));
+
+        let stats = world.resource::<Profile>().stats.clone();
+        assert_eq!((stats.points_won, stats.points_lost), (2, 1));
+        assert_eq!(stats.longest_rally, 3);
+        assert_eq!(stats.fastest_return, 320.0);
+        assert_eq!((stats.matches_played, stats.matches_won), (1, 1));
+        assert_eq!(stats.difficulties[&AiDifficulty::Hard].won, 1);
+        assert_eq!(stats.time_played, 2.0);
+
+        // Versus matches only count towards the match in progress
+        world.insert_resource(GameMode::Versus);
+        run_frame(&mut system, &mut world, vec![hit(300.0, true, 1), goal(true, 1)]);
+        assert_eq!(world.resource::<Profile>().stats.points_won, 2);
+        assert_eq!(world.resource::<MatchStats>().duration, 2.5);
+    }
+}
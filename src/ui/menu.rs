@@ -0,0 +1,359 @@
+// src/ui/menu.rs
+use crate::engine::Config;
+use crate::engine::config::AiDifficulty;
+use crate::engine::framebuffer::{Framebuffer, with_alpha};
+use crate::engine::viewport::ScaleMode;
+use crate::profile::{self, Profile};
+use crate::ui::{Input, UiState};
+
+const SCALE_MODES: [ScaleMode; 3] = [ScaleMode::Integer, ScaleMode::Stretch, ScaleMode::Letterbox];
+
+/// What the player picked on one of the menu screens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    Play,       // A match against the AI
+    TwoPlayers, // A versus match at one keyboard
+    Options,
+    Stats,
+    Quit,        // Ask before quitting the game
+    ConfirmQuit, // Yes, quit
+    SaveOptions, // Apply and save the options screen's settings
+    Back,        // Leave a screen without saving
+    Resume,      // Back to the paused match
+    Restart,     // Start the paused match over
+    QuitToMenu,  // Abandon the paused match
+}
+
+/// Darkens the frozen court behind a menu.
+fn dim(frame: &mut Framebuffer) {
+    let (width, height) = (frame.width() as i32, frame.height() as i32);
+    frame.fill_rect(0, 0, width, height, with_alpha(0x000000, 0.6));
+}
+
+/// The title screen.
+#[derive(Debug, Default)]
+pub struct MainMenu {
+    ui: UiState,
+}
+
+#[allow(dead_code)]
+impl MainMenu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn show(&mut self, frame: &mut Framebuffer, input: Input) -> Option<MenuAction> {
+        dim(frame);
+        let top = frame.height() as i32 / 4;
+        let mut ui = self.ui.begin(frame, input, top);
+        ui.title("PONG");
+        let actions = [
+            ("PLAY", MenuAction::Play),
+            ("2 PLAYERS", MenuAction::TwoPlayers),
+            ("OPTIONS", MenuAction::Options),
+            ("STATS", MenuAction::Stats),
+            ("QUIT", MenuAction::Quit),
+        ];
+        let mut picked = ui.input().back.then_some(MenuAction::Quit);
+        for (label, action) in actions {
+            if ui.button(label) {
+                picked = Some(action);
+            }
+        }
+        picked
+    }
+}
+
+/// The overlay over a paused match.
+#[derive(Debug, Default)]
+pub struct PauseMenu {
+    ui: UiState,
+}
+
+#[allow(dead_code)]
+impl PauseMenu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Puts the focus back on Resume, for the next time the game pauses.
+    pub fn reset(&mut self) {
+        self.ui.set_focus(0);
+    }
+
+    pub fn show(&mut self, frame: &mut Framebuffer, input: Input) -> Option<MenuAction> {
+        dim(frame);
+        let top = frame.height() as i32 / 4;
+        let mut ui = self.ui.begin(frame, input, top);
+        ui.title("PAUSED");
+        let actions = [
+            ("RESUME", MenuAction::Resume),
+            ("RESTART", MenuAction::Restart),
+            ("OPTIONS", MenuAction::Options),
+            ("QUIT TO MENU", MenuAction::QuitToMenu),
+            ("QUIT GAME", MenuAction::Quit),
+        ];
+        let mut picked = ui.input().back.then_some(MenuAction::Resume);
+        for (label, action) in actions {
+            if ui.button(label) {
+                picked = Some(action);
+            }
+        }
+        picked
+    }
+}
+
+/// Asks whether to really quit, over whatever screen is showing. No has
+/// the focus to begin with, so a stray Enter doesn't quit.
+#[derive(Debug)]
+pub struct QuitDialog {
+    ui: UiState,
+}
+
+#[allow(dead_code)]
+impl QuitDialog {
+    pub fn new() -> Self {
+        let mut ui = UiState::new();
+        ui.set_focus(1);
+        Self { ui }
+    }
+
+    pub fn show(&mut self, frame: &mut Framebuffer, input: Input) -> Option<MenuAction> {
+        dim(frame);
+        let top = frame.height() as i32 / 2 - 48;
+        let mut ui = self.ui.begin(frame, input, top);
+        ui.label("QUIT PONG?");
+        let back = ui.input().back;
+        if ui.button("YES") {
+            Some(MenuAction::ConfirmQuit)
+        } else if ui.button("NO") || back {
+            Some(MenuAction::Back)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for QuitDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Edits a copy of the settings players usually want to change. Nothing
+/// touches the running config until the player saves.
+pub struct OptionsScreen {
+    ui: UiState,
+    config: Config,
+}
+
+#[allow(dead_code)]
+impl OptionsScreen {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            ui: UiState::new(),
+            config: config.clone(),
+        }
+    }
+
+    /// The settings as edited so far.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Copies the settings this screen edits into `config`, leaving the
+    /// rest alone.
+    pub fn apply_to(&self, config: &mut Config) {
+        let edited = &self.config;
+        config.ai_difficulty = edited.ai_difficulty;
+        config.winning_score = edited.winning_score;
+        config.audio_volume = edited.audio_volume;
+        config.audio_muted = edited.audio_muted;
+        config.fullscreen = edited.fullscreen;
+        config.scale_mode = edited.scale_mode;
+        config.particles_enabled = edited.particles_enabled;
+        config.ball_trail_enabled = edited.ball_trail_enabled;
+        config.screen_shake_enabled = edited.screen_shake_enabled;
+        config.multiball_enabled = edited.multiball_enabled;
+    }
+
+    pub fn show(&mut self, frame: &mut Framebuffer, input: Input) -> Option<MenuAction> {
+        dim(frame);
+        let config = &mut self.config;
+        let mut ui = self.ui.begin(frame, input, 24);
+        ui.title("OPTIONS");
+
+        let difficulties = AiDifficulty::ALL.map(|d| d.name().to_uppercase());
+        let difficulties: Vec<&str> = difficulties.iter().map(String::as_str).collect();
+        let mut difficulty = AiDifficulty::ALL
+            .iter()
+            .position(|&d| d == config.ai_difficulty)
+            .unwrap_or(0);
+        if ui.list("DIFFICULTY", &difficulties, &mut difficulty) {
+            config.ai_difficulty = AiDifficulty::ALL[difficulty];
+        }
+
+        let mut winning_score = config.winning_score as f32;
+        if ui.slider("POINTS TO WIN", &mut winning_score, 1.0..=21.0, 1.0) {
+            config.winning_score = winning_score as u32;
+        }
+        ui.slider("VOLUME", &mut config.audio_volume, 0.0..=1.0, 0.1);
+        ui.toggle("MUTE", &mut config.audio_muted);
+        ui.toggle("FULLSCREEN", &mut config.fullscreen);
+
+        let mut scale_mode = SCALE_MODES
+            .iter()
+            .position(|&mode| mode == config.scale_mode)
+            .unwrap_or(0);
+        if ui.list(
+            "SCALING",
+            &["INTEGER", "STRETCH", "LETTERBOX"],
+            &mut scale_mode,
+        ) {
+            config.scale_mode = SCALE_MODES[scale_mode];
+        }
+        ui.toggle("PARTICLES", &mut config.particles_enabled);
+        ui.toggle("BALL TRAIL", &mut config.ball_trail_enabled);
+        ui.toggle("SCREEN SHAKE", &mut config.screen_shake_enabled);
+        ui.toggle("MULTIBALL", &mut config.multiball_enabled);
+
+        ui.space(8);
+        let back = ui.input().back;
+        if ui.button("SAVE") {
+            Some(MenuAction::SaveOptions)
+        } else if ui.button("BACK") || back {
+            Some(MenuAction::Back)
+        } else {
+            None
+        }
+    }
+}
+
+/// The profile's lifetime stats, with a way back to the menu.
+#[derive(Debug, Default)]
+pub struct StatsScreen {
+    ui: UiState,
+}
+
+#[allow(dead_code)]
+impl StatsScreen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn show(
+        &mut self,
+        frame: &mut Framebuffer,
+        input: Input,
+        profile: Option<&Profile>,
+    ) -> Option<MenuAction> {
+        let top = match profile {
+            Some(profile) => {
+                profile::screen::draw(frame, profile);
+                frame.height() as i32 - 64
+            }
+            None => {
+                dim(frame);
+                frame.height() as i32 / 2 - 32
+            }
+        };
+        let mut ui = self.ui.begin(frame, input, top);
+        if profile.is_none() {
+            ui.label("NO PROFILE LOADED");
+        }
+        let back = ui.input().back;
+        (ui.button("BACK") || back).then_some(MenuAction::Back)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn menu_picks_by_keyboard() {
+        let mut frame = Framebuffer::new(800, 600);
+        let mut menu = MainMenu::new();
+        assert_eq!(menu.show(&mut frame, Input::default()), None);
+        let down = Input {
+            down: true,
+            ..Input::default()
+        };
+        for _ in 0..6 {
+            menu.show(&mut frame, down);
+        }
+        let activate = Input {
+            activate: true,
+            ..Input::default()
+        };
+        // Six downs through five buttons wraps round to the second
+        assert_eq!(
+            menu.show(&mut frame, activate),
+            Some(MenuAction::TwoPlayers)
+        );
+    }
+
+    #[test]
+    fn pause_menu_and_quit_dialog() {
+        let mut frame = Framebuffer::new(800, 600);
+        let back = Input {
+            back: true,
+            ..Input::default()
+        };
+        let activate = Input {
+            activate: true,
+            ..Input::default()
+        };
+
+        let mut pause = PauseMenu::new();
+        assert_eq!(pause.show(&mut frame, back), Some(MenuAction::Resume));
+        assert_eq!(pause.show(&mut frame, activate), Some(MenuAction::Resume));
+
+        // Enter straight away keeps playing, only Yes quits
+        let mut dialog = QuitDialog::new();
+        assert_eq!(dialog.show(&mut frame, activate), Some(MenuAction::Back));
+        let up = Input {
+            up: true,
+            activate: true,
+            ..Input::default()
+        };
+        assert_eq!(dialog.show(&mut frame, up), Some(MenuAction::ConfirmQuit));
+    }
+
+    #[test]
+    fn options_edit_a_copy() {
+        let mut frame = Framebuffer::new(800, 600);
+        let running = Config::default();
+        let mut screen = OptionsScreen::new(&running);
+        screen.show(&mut frame, Input::default());
+
+        // Difficulty is first, points to win second
+        let right = Input {
+            right: true,
+            ..Input::default()
+        };
+        screen.show(&mut frame, right);
+        let down = Input {
+            down: true,
+            right: true,
+            ..Input::default()
+        };
+        screen.show(&mut frame, down);
+        assert_eq!(screen.config().ai_difficulty, AiDifficulty::Hard);
+        assert_eq!(screen.config().winning_score, running.winning_score + 1);
+
+        let mut saved = Config {
+            paddle_speed: 123.0,
+            ..Config::default()
+        };
+        screen.apply_to(&mut saved);
+        assert_eq!(saved.ai_difficulty, AiDifficulty::Hard);
+        assert_eq!(saved.paddle_speed, 123.0);
+
+        let back = Input {
+            back: true,
+            ..Input::default()
+        };
+        assert_eq!(screen.show(&mut frame, back), Some(MenuAction::Back));
+    }
+}
@@ -0,0 +1,457 @@
+// src/ui/mod.rs
+//
+// A small immediate-mode UI for menus. Widgets are laid out top to bottom
+// in a centred column, drawn straight into the frame and report what the
+// player did with them as they are drawn. Only the keyboard focus survives
+// between frames, in a `UiState` kept by each screen.
+pub mod menu;
+
+use std::ops::RangeInclusive;
+
+use minifb::Key;
+
+use crate::engine::Engine;
+use crate::engine::font::{LINE_HEIGHT, text_width};
+use crate::engine::framebuffer::{Framebuffer, opaque, with_alpha};
+
+pub use menu::{MainMenu, MenuAction, OptionsScreen, PauseMenu, QuitDialog, StatsScreen};
+
+const WIDTH: i32 = 360; // Of every widget
+const ROW_HEIGHT: i32 = (LINE_HEIGHT * TEXT_SCALE) as i32 + 8;
+const SPACING: i32 = 4;
+const PADDING: i32 = 8;
+const TEXT_SCALE: usize = 2;
+const TITLE_SCALE: usize = 4;
+const SLIDER_WIDTH: i32 = 120;
+
+const TEXT: u32 = opaque(0xFFFFFF);
+const DIM: u32 = opaque(0xA0A0A0);
+const FOCUS: u32 = opaque(0xFFFF80);
+const EDGE: u32 = opaque(0x606080);
+const FILL: u32 = opaque(0x40A0FF);
+
+/// One frame of menu input, read from the engine or built by hand.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Input {
+    pub up: bool,
+    pub down: bool,
+    pub left: bool,
+    pub right: bool,
+    pub activate: bool,            // Enter or Space
+    pub back: bool,                // Escape or Backspace
+    pub mouse: Option<(f32, f32)>, // In logical pixels
+    pub mouse_down: bool,
+    pub clicked: bool, // The button went down this frame
+}
+
+impl Input {
+    /// The keys and mouse of the last presented frame. Tab moves down the
+    /// menu like the down arrow.
+    pub fn read(engine: &Engine) -> Self {
+        Self {
+            up: engine.key_repeated(Key::Up),
+            down: engine.key_repeated(Key::Down) || engine.key_repeated(Key::Tab),
+            left: engine.key_repeated(Key::Left),
+            right: engine.key_repeated(Key::Right),
+            activate: engine.key_pressed(Key::Enter) || engine.key_pressed(Key::Space),
+            back: engine.key_pressed(Key::Escape) || engine.key_pressed(Key::Backspace),
+            mouse: engine.mouse_position(),
+            mouse_down: engine.mouse_down(),
+            clicked: engine.mouse_clicked(),
+        }
+    }
+}
+
+/// What a screen keeps between frames: which widget has the focus.
+#[derive(Debug, Default)]
+pub struct UiState {
+    focus: usize,
+    count: usize,              // Focusable widgets last frame, for wrapping
+    mouse: Option<(f32, f32)>, // Where the mouse was, so only moving it steals the focus
+}
+
+#[allow(dead_code)]
+impl UiState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index of the focused widget, counting focusable widgets in the order
+    /// they are added.
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    pub fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+    }
+
+    /// Starts a frame of widgets laid out down from `top`, moving the focus
+    /// with the arrow keys first.
+    pub fn begin<'a>(&'a mut self, frame: &'a mut Framebuffer, input: Input, top: i32) -> Ui<'a> {
+        if self.count > 0 {
+            if input.down {
+                self.focus = (self.focus + 1) % self.count;
+            }
+            if input.up {
+                self.focus = (self.focus + self.count - 1) % self.count;
+            }
+            self.focus = self.focus.min(self.count - 1);
+        }
+        let moved = input.mouse.is_some() && (input.mouse != self.mouse || input.clicked);
+        self.mouse = input.mouse;
+        self.count = 0;
+
+        let left = (frame.width() as i32 - WIDTH) / 2;
+        Ui {
+            state: self,
+            frame,
+            input,
+            moved,
+            left,
+            y: top,
+        }
+    }
+}
+
+/// A screen position and size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+impl Rect {
+    fn contains(&self, point: Option<(f32, f32)>) -> bool {
+        point.is_some_and(|(x, y)| {
+            let (x, y) = (x as i32, y as i32);
+            x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+        })
+    }
+}
+
+/// A widget being added: where it is and how the player is touching it.
+struct Slot {
+    rect: Rect,
+    focused: bool,
+    hovered: bool,
+    clicked: bool,
+}
+
+/// One frame's widgets, from `UiState::begin`.
+pub struct Ui<'a> {
+    state: &'a mut UiState,
+    frame: &'a mut Framebuffer,
+    input: Input,
+    moved: bool, // The mouse moved or clicked, so hovering takes the focus
+    left: i32,
+    y: i32,
+}
+
+#[allow(dead_code)]
+impl Ui<'_> {
+    pub fn input(&self) -> &Input {
+        &self.input
+    }
+
+    /// Large centred text.
+    pub fn title(&mut self, text: &str) {
+        let x = (self.frame.width() as i32 - text_width(text, TITLE_SCALE) as i32) / 2;
+        self.frame.text(text, x, self.y, TITLE_SCALE, FOCUS);
+        self.y += (LINE_HEIGHT * TITLE_SCALE) as i32 + SPACING * 4;
+    }
+
+    /// Dim centred text that can't take the focus.
+    pub fn label(&mut self, text: &str) {
+        let x = (self.frame.width() as i32 - text_width(text, TEXT_SCALE) as i32) / 2;
+        self.frame
+            .text(text, x, self.y + PADDING / 2, TEXT_SCALE, DIM);
+        self.y += ROW_HEIGHT + SPACING;
+    }
+
+    pub fn space(&mut self, height: i32) {
+        self.y += height;
+    }
+
+    /// True when clicked, or activated while focused.
+    pub fn button(&mut self, label: &str) -> bool {
+        let slot = self.slot();
+        self.background(&slot);
+        let x = slot.rect.x + (WIDTH - text_width(label, TEXT_SCALE) as i32) / 2;
+        self.frame.text(
+            label,
+            x,
+            slot.rect.y + PADDING / 2,
+            TEXT_SCALE,
+            color(&slot),
+        );
+        slot.clicked || (slot.focused && self.input.activate)
+    }
+
+    /// An on/off setting, flipped by clicking, activating or left/right.
+    /// Returns true when it changed.
+    pub fn toggle(&mut self, label: &str, value: &mut bool) -> bool {
+        let slot = self.slot();
+        let changed = slot.clicked
+            || (slot.focused && (self.input.activate || self.input.left || self.input.right));
+        if changed {
+            *value = !*value;
+        }
+        self.row(&slot, label, if *value { "ON" } else { "OFF" });
+        changed
+    }
+
+    /// A number in `range`, moved by `step` with left/right or set by
+    /// clicking or dragging along the bar. Returns true when it changed.
+    pub fn slider(
+        &mut self,
+        label: &str,
+        value: &mut f32,
+        range: RangeInclusive<f32>,
+        step: f32,
+    ) -> bool {
+        let slot = self.slot();
+        let (min, max) = (*range.start(), *range.end());
+        let bar = Rect {
+            x: slot.rect.x + WIDTH - PADDING - SLIDER_WIDTH,
+            y: slot.rect.y,
+            width: SLIDER_WIDTH,
+            height: slot.rect.height,
+        };
+
+        let old = *value;
+        let mut new = old;
+        if slot.focused && self.input.left {
+            new -= step;
+        }
+        if slot.focused && self.input.right {
+            new += step;
+        }
+        if let Some((x, _)) = self.input.mouse.filter(|_| self.input.mouse_down)
+            && bar.contains(self.input.mouse)
+        {
+            let amount = (x - bar.x as f32) / (bar.width - 1) as f32;
+            new = min + amount.clamp(0.0, 1.0) * (max - min);
+        }
+        if new != old {
+            // Snap to the steps so repeated presses don't drift
+            new = (min + ((new - min) / step).round() * step).clamp(min, max);
+            *value = new;
+        }
+
+        let decimals = match step {
+            s if s >= 1.0 => 0,
+            s if s >= 0.1 => 1,
+            _ => 2,
+        };
+        let text = format!("{:.*}", decimals, *value);
+        self.row(&slot, label, "");
+        let text_x = bar.x - PADDING - text_width(&text, TEXT_SCALE) as i32;
+        let text_y = slot.rect.y + PADDING / 2;
+        self.frame
+            .text(&text, text_x, text_y, TEXT_SCALE, color(&slot));
+
+        let track_y = bar.y + bar.height / 2 - 3;
+        let filled = if max > min {
+            ((*value - min) / (max - min) * bar.width as f32) as i32
+        } else {
+            0
+        };
+        self.frame.fill_rect(bar.x, track_y, bar.width, 6, EDGE);
+        self.frame.fill_rect(bar.x, track_y, filled, 6, FILL);
+        *value != old
+    }
+
+    /// One of `items`, shown as `< ITEM >` and stepped through with
+    /// left/right, activating or clicking. Returns true when it changed.
+    pub fn list(&mut self, label: &str, items: &[&str], selected: &mut usize) -> bool {
+        let slot = self.slot();
+        let old = *selected;
+        if !items.is_empty() {
+            let count = items.len();
+            if slot.focused && self.input.left {
+                *selected = (*selected + count - 1) % count;
+            }
+            if slot.clicked || (slot.focused && (self.input.right || self.input.activate)) {
+                *selected = (*selected + 1) % count;
+            }
+            *selected = (*selected).min(count - 1);
+        }
+        let item = items.get(*selected).copied().unwrap_or("");
+        self.row(&slot, label, &format!("< {} >", item));
+        *selected != old
+    }
+
+    /// Claims the next row for a focusable widget.
+    fn slot(&mut self) -> Slot {
+        let index = self.state.count;
+        self.state.count += 1;
+        let rect = Rect {
+            x: self.left,
+            y: self.y,
+            width: WIDTH,
+            height: ROW_HEIGHT,
+        };
+        self.y += ROW_HEIGHT + SPACING;
+
+        let hovered = rect.contains(self.input.mouse);
+        if hovered && self.moved {
+            self.state.focus = index;
+        }
+        Slot {
+            rect,
+            focused: self.state.focus == index,
+            hovered,
+            clicked: hovered && self.input.clicked,
+        }
+    }
+
+    fn background(&mut self, slot: &Slot) {
+        let Rect {
+            x,
+            y,
+            width,
+            height,
+        } = slot.rect;
+        let fill = if slot.focused { 0.9 } else { 0.7 };
+        self.frame
+            .fill_rect(x, y, width, height, with_alpha(0x101018, fill));
+        let edge = if slot.focused { FOCUS } else { EDGE };
+        self.frame.rect(x, y, width, height, edge);
+    }
+
+    /// A setting row: the label on the left, its value on the right.
+    fn row(&mut self, slot: &Slot, label: &str, value: &str) {
+        self.background(slot);
+        let y = slot.rect.y + PADDING / 2;
+        let color = color(slot);
+        self.frame
+            .text(label, slot.rect.x + PADDING, y, TEXT_SCALE, color);
+        let value_x = slot.rect.x + WIDTH - PADDING - text_width(value, TEXT_SCALE) as i32;
+        self.frame.text(value, value_x, y, TEXT_SCALE, color);
+    }
+}
+
+fn color(slot: &Slot) -> u32 {
+    if slot.focused || slot.hovered {
+        FOCUS
+    } else {
+        TEXT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOP: i32 = 100;
+
+    /// The centre of the `index`th row from `TOP` in an 800 wide frame.
+    fn row_center(index: i32) -> (f32, f32) {
+        let y = TOP + index * (ROW_HEIGHT + SPACING) + ROW_HEIGHT / 2;
+        (400.0, y as f32)
+    }
+
+    #[test]
+    fn keyboard_moves_the_focus_and_activates() {
+        let mut frame = Framebuffer::new(800, 600);
+        let mut state = UiState::new();
+        let mut sound = true;
+        let mut frame_of = |state: &mut UiState, input: Input, sound: &mut bool| {
+            let mut ui = state.begin(&mut frame, input, TOP);
+            let play = ui.button("PLAY");
+            ui.label("NOT FOCUSABLE");
+            ui.toggle("SOUND", sound);
+            let quit = ui.button("QUIT");
+            (play, quit)
+        };
+
+        assert_eq!(
+            frame_of(&mut state, Input::default(), &mut sound),
+            (false, false)
+        );
+        let activate = Input {
+            activate: true,
+            ..Input::default()
+        };
+        assert_eq!(frame_of(&mut state, activate, &mut sound), (true, false));
+
+        // Down skips the label, and up from the top wraps to the bottom
+        let down = Input {
+            down: true,
+            ..Input::default()
+        };
+        frame_of(&mut state, down, &mut sound);
+        assert_eq!(state.focus(), 1);
+        let right = Input {
+            right: true,
+            ..Input::default()
+        };
+        frame_of(&mut state, right, &mut sound);
+        assert!(!sound);
+        let up = Input {
+            up: true,
+            activate: true,
+            ..Input::default()
+        };
+        state.set_focus(0);
+        assert_eq!(frame_of(&mut state, up, &mut sound), (false, true));
+        assert!(frame.pixels().contains(&(FOCUS & 0xFFFFFF)));
+    }
+
+    #[test]
+    fn mouse_hovers_clicks_and_drags() {
+        let mut frame = Framebuffer::new(800, 600);
+        let mut state = UiState::new();
+        let mut volume = 0.5;
+        let mut mode = 0;
+        let mut frame_of =
+            |state: &mut UiState, input: Input, volume: &mut f32, mode: &mut usize| {
+                let mut ui = state.begin(&mut frame, input, TOP);
+                ui.slider("VOLUME", volume, 0.0..=1.0, 0.1);
+                ui.list("MODE", &["A", "B", "C"], mode);
+                ui.button("BACK")
+            };
+
+        // Hovering takes the focus, clicking a list steps it
+        let click_list = Input {
+            mouse: Some(row_center(1)),
+            mouse_down: true,
+            clicked: true,
+            ..Input::default()
+        };
+        frame_of(&mut state, click_list, &mut volume, &mut mode);
+        assert_eq!((state.focus(), mode), (1, 1));
+
+        // A mouse resting elsewhere doesn't fight the keyboard
+        let rest = Input {
+            mouse: Some(row_center(1)),
+            up: true,
+            left: true,
+            ..Input::default()
+        };
+        frame_of(&mut state, rest, &mut volume, &mut mode);
+        assert_eq!(state.focus(), 0);
+        assert!((volume - 0.4).abs() < 1e-6);
+
+        // Dragging to the end of the slider's bar sets it to the maximum
+        let bar_end = (400 + WIDTH / 2 - PADDING - 1) as f32;
+        let drag = Input {
+            mouse: Some((bar_end, row_center(0).1)),
+            mouse_down: true,
+            ..Input::default()
+        };
+        frame_of(&mut state, drag, &mut volume, &mut mode);
+        assert_eq!(volume, 1.0);
+
+        let click_back = Input {
+            mouse: Some(row_center(2)),
+            clicked: true,
+            mouse_down: true,
+            ..Input::default()
+        };
+        assert!(frame_of(&mut state, click_back, &mut volume, &mut mode));
+    }
+}